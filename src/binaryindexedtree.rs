@@ -1,5 +1,5 @@
 use super::weightedunionfind::Group;
-use crate::util::Commutativity;
+use crate::util::{to_range, Commutativity};
 
 /// アーベル群のトレイト
 pub trait AbelianGroup: Group + Commutativity {}
@@ -22,6 +22,23 @@ impl<G: AbelianGroup> BinaryIndexedTree<G> {
         Self((0..n).map(|_| group.e()).collect(), group)
     }
 
+    /// 配列`vec`からBinaryIndexedTreeを構築する.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*n*)
+    #[must_use]
+    pub fn from_vec(group: G, vec: Vec<G::T>) -> Self {
+        let mut vec = vec.into_boxed_slice();
+        for i in 0..vec.len() {
+            let j = i | (i + 1);
+            if j < vec.len() {
+                vec[j] = group.op(&vec[j], &vec[i]);
+            }
+        }
+        Self(vec, group)
+    }
+
     /// BinaryIndexedTreeの長さを返す
     ///
     /// # Time complexity
@@ -93,6 +110,164 @@ impl<G: AbelianGroup> BinaryIndexedTree<G> {
         }
         s
     }
+
+    /// `index`番目の要素を取得する
+    ///
+    /// # Constraints
+    ///
+    /// - `index < self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    #[must_use]
+    pub fn get(&self, index: usize) -> G::T {
+        debug_assert!(index < self.len());
+        self.sum(index..=index)
+    }
+
+    /// `index`番目の要素を`val`に設定する
+    ///
+    /// # Constraints
+    ///
+    /// - `index < self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn set(&mut self, index: usize, val: &G::T) {
+        let diff = self.1.opinv(val, &self.get(index));
+        self.add(index, &diff);
+    }
+
+    /// `self.sum(..=x) >= w`となる最小の`x`を返す. 存在しない場合は`self.len()`を返す
+    ///
+    /// # Constraints
+    ///
+    /// - 全ての要素が`group.e()`以上である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    #[must_use]
+    pub fn lower_bound(&self, w: &G::T) -> usize
+    where
+        G::T: PartialOrd,
+    {
+        let len = self.len();
+        let mut index = 0;
+        let mut s = self.1.e();
+        let mut step = if len == 0 { 0 } else { 1 << len.ilog2() };
+        while step > 0 {
+            if index + step <= len {
+                let t = self.1.op(&s, &self.0[index + step - 1]);
+                if t < *w {
+                    index += step;
+                    s = t;
+                }
+            }
+            step >>= 1;
+        }
+        index
+    }
+}
+
+/// `val`を`n`回掛けたものを計算する
+fn times<G: AbelianGroup>(group: &G, val: &G::T, mut n: usize) -> G::T {
+    let mut r = group.e();
+    let mut x = val.clone();
+    while n > 0 {
+        if n & 1 == 1 {
+            r = group.op(&r, &x);
+        }
+        x = group.op(&x, &x);
+        n >>= 1;
+    }
+    r
+}
+
+/// 区間加算と区間和を処理できるBinaryIndexedTree
+#[derive(Clone)]
+pub struct RangeAddBIT<G: AbelianGroup>(BinaryIndexedTree<G>, BinaryIndexedTree<G>);
+
+impl<G: AbelianGroup> RangeAddBIT<G> {
+    /// 新しい長さ`n`のRangeAddBITを構築する.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*n*)
+    #[must_use]
+    pub fn new(group: G, n: usize) -> Self
+    where
+        G: Clone,
+    {
+        Self(
+            BinaryIndexedTree::new(group.clone(), n + 1),
+            BinaryIndexedTree::new(group, n + 1),
+        )
+    }
+
+    /// RangeAddBITの長さを返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len() - 1
+    }
+
+    /// RangeAddBITが空か判定する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `range`の範囲の要素に`val`を掛ける
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn add(&mut self, range: impl std::ops::RangeBounds<usize>, val: &G::T) {
+        let (left, right) = to_range(range, self.len());
+        let g = &self.0 .1;
+        let inv = g.inv(val);
+        let l = times(g, val, left);
+        let r = times(g, &inv, right);
+        self.0.add(left, val);
+        self.0.add(right, &inv);
+        self.1.add(left, &l);
+        self.1.add(right, &r);
+    }
+
+    /// 先頭`index`個の要素の総積
+    fn prefix(&self, index: usize) -> G::T {
+        let g = &self.0 .1;
+        g.opinv(&times(g, &self.0.sum(..index), index), &self.1.sum(..index))
+    }
+
+    /// `range`の範囲の要素の総積を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    #[must_use]
+    pub fn sum(&self, range: impl std::ops::RangeBounds<usize>) -> G::T {
+        let (left, right) = to_range(range, self.len());
+        self.0 .1.opinv(&self.prefix(right), &self.prefix(left))
+    }
 }
 
 #[cfg(test)]
@@ -138,4 +313,38 @@ mod test {
         assert_eq!(v.sum(3..9), 3);
         assert_eq!(v.sum(..), 15);
     }
+
+    #[test]
+    fn lower_bound_set_range_add() {
+        use crate::monoids::Sum;
+
+        let v = vec![3i64, 0, 4, 1, 5, 9, 2, 6];
+        let mut bit = BinaryIndexedTree::from_vec(Sum::new(), v.clone());
+        for (i, &x) in v.iter().enumerate() {
+            assert_eq!(bit.get(i), x);
+        }
+        for w in 0..35 {
+            let expected = (0..v.len())
+                .find(|&x| v[..=x].iter().sum::<i64>() >= w)
+                .unwrap_or(v.len());
+            assert_eq!(bit.lower_bound(&w), expected);
+        }
+        bit.set(3, &10);
+        assert_eq!(bit.get(3), 10);
+        assert_eq!(bit.sum(2..5), 19);
+
+        let mut w = [0i64; 10];
+        let mut bit = RangeAddBIT::new(Sum::new(), 10);
+        for (l, r, x) in [(2, 7, 3), (0, 10, -1), (5, 6, 8), (4, 10, 2), (0, 0, 5)] {
+            bit.add(l..r, &x);
+            for y in &mut w[l..r] {
+                *y += x;
+            }
+            for l in 0..=10 {
+                for r in l..=10 {
+                    assert_eq!(bit.sum(l..r), w[l..r].iter().sum());
+                }
+            }
+        }
+    }
 }
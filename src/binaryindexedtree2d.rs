@@ -0,0 +1,280 @@
+use super::{binaryindexedtree::AbelianGroup, util::to_range};
+
+/// 1次元のBinaryIndexedTreeとみなした`tree`の`index`番目の要素に`val`を掛ける
+fn add<G: AbelianGroup>(group: &G, tree: &mut [G::T], mut index: usize, val: &G::T) {
+    while index < tree.len() {
+        tree[index] = group.op(&tree[index], val);
+        index |= index + 1;
+    }
+}
+
+/// 1次元のBinaryIndexedTreeとみなした`tree`の先頭`index`個の要素の総積
+fn prefix<G: AbelianGroup>(group: &G, tree: &[G::T], mut index: usize) -> G::T {
+    let mut s = group.e();
+    while index > 0 {
+        s = group.op(&s, &tree[index - 1]);
+        index &= index - 1;
+    }
+    s
+}
+
+/// 2次元BinaryIndexedTree
+///
+/// 2次元配列の一点加算と長方形領域の総和を処理する
+#[derive(Clone)]
+pub struct BinaryIndexedTree2D<G: AbelianGroup>(Box<[G::T]>, usize, usize, G);
+
+impl<G: AbelianGroup> BinaryIndexedTree2D<G> {
+    /// 新しい`h`行`w`列のBinaryIndexedTree2Dを構築する.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*HW*)
+    #[must_use]
+    pub fn new(group: G, h: usize, w: usize) -> Self {
+        Self((0..h * w).map(|_| group.e()).collect(), h, w, group)
+    }
+
+    /// 行数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.1
+    }
+
+    /// 列数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.2
+    }
+
+    /// `i`行`j`列目の要素に`val`を掛ける
+    ///
+    /// # Constraints
+    ///
+    /// - `i < self.height()`
+    /// - `j < self.width()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *H* log *W*)
+    pub fn add(&mut self, mut i: usize, j: usize, val: &G::T) {
+        debug_assert!(i < self.1 && j < self.2);
+        let w = self.2;
+        while i < self.1 {
+            add(&self.3, &mut self.0[i * w..(i + 1) * w], j, val);
+            i |= i + 1;
+        }
+    }
+
+    /// 先頭`i`行, 先頭`j`列の長方形領域の総積
+    fn prefix(&self, mut i: usize, j: usize) -> G::T {
+        let w = self.2;
+        let mut s = self.3.e();
+        while i > 0 {
+            s = self
+                .3
+                .op(&s, &prefix(&self.3, &self.0[(i - 1) * w..i * w], j));
+            i &= i - 1;
+        }
+        s
+    }
+
+    /// `rows`行目と`cols`列目の範囲の長方形領域の総和を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `rows`は`0..self.height()`に含まれる区間である.
+    /// - `cols`は`0..self.width()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *H* log *W*)
+    #[must_use]
+    pub fn sum(
+        &self,
+        rows: impl std::ops::RangeBounds<usize>,
+        cols: impl std::ops::RangeBounds<usize>,
+    ) -> G::T {
+        let (top, bottom) = to_range(rows, self.1);
+        let (left, right) = to_range(cols, self.2);
+        let x = self
+            .3
+            .op(&self.prefix(bottom, right), &self.prefix(top, left));
+        let y = self
+            .3
+            .op(&self.prefix(top, right), &self.prefix(bottom, left));
+        self.3.opinv(&x, &y)
+    }
+}
+
+/// 座標圧縮された2次元BinaryIndexedTree
+///
+/// 加算する点を先読みし, 座標が大きい場合でも一点加算と長方形領域の総和を処理する
+#[derive(Clone)]
+pub struct OfflineBinaryIndexedTree2D<K: Ord + Copy, G: AbelianGroup> {
+    /// 圧縮後のx座標
+    xs: Box<[K]>,
+    /// x方向の各ノードが持つy座標を連結したもの
+    ys: Box<[K]>,
+    /// x方向の各ノードについて, `ys`と`tree`の中での開始位置
+    offsets: Box<[usize]>,
+    tree: Box<[G::T]>,
+    group: G,
+}
+
+impl<K: Ord + Copy, G: AbelianGroup> OfflineBinaryIndexedTree2D<K, G> {
+    /// 加算する可能性のある点の集合からOfflineBinaryIndexedTree2Dを構築する.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*N* log *N*)
+    #[must_use]
+    pub fn new(group: G, points: impl IntoIterator<Item = (K, K)>) -> Self {
+        let mut points = points.into_iter().collect::<Vec<_>>();
+        points.sort_unstable_by_key(|p| p.1);
+        let mut xs = points.iter().map(|p| p.0).collect::<Vec<_>>();
+        xs.sort_unstable();
+        xs.dedup();
+        let mut nodes = vec![vec![]; xs.len()];
+        for &(x, y) in &points {
+            let mut i = xs.partition_point(|&v| v < x);
+            while i < xs.len() {
+                if nodes[i].last() != Some(&y) {
+                    nodes[i].push(y);
+                }
+                i |= i + 1;
+            }
+        }
+        let mut offsets = Vec::with_capacity(xs.len() + 1);
+        offsets.push(0);
+        for node in &nodes {
+            offsets.push(offsets.last().unwrap() + node.len());
+        }
+        let ys = nodes.concat();
+        let tree = (0..ys.len()).map(|_| group.e()).collect();
+        Self {
+            xs: xs.into(),
+            ys: ys.into(),
+            offsets: offsets.into(),
+            tree,
+            group,
+        }
+    }
+
+    /// 点`(x, y)`に`val`を掛ける
+    ///
+    /// # Constraints
+    ///
+    /// - `(x, y)`は構築時に与えた点のいずれかである.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log² *N*)
+    pub fn add(&mut self, x: K, y: K, val: &G::T) {
+        let mut i = self.xs.partition_point(|&v| v < x);
+        debug_assert!(self.xs.get(i) == Some(&x));
+        while i < self.xs.len() {
+            let (s, t) = (self.offsets[i], self.offsets[i + 1]);
+            let j = self.ys[s..t].partition_point(|&v| v < y);
+            debug_assert!(self.ys.get(s + j) == Some(&y));
+            add(&self.group, &mut self.tree[s..t], j, val);
+            i |= i + 1;
+        }
+    }
+
+    /// x圧縮座標が`i`未満でy座標が`ys`に含まれる点の総積
+    fn prefix(&self, mut i: usize, ys: &std::ops::Range<K>) -> G::T {
+        let mut sum = self.group.e();
+        while i > 0 {
+            let (s, t) = (self.offsets[i - 1], self.offsets[i]);
+            let l = self.ys[s..t].partition_point(|&v| v < ys.start);
+            let r = self.ys[s..t].partition_point(|&v| v < ys.end);
+            let tree = &self.tree[s..t];
+            sum = self.group.op(
+                &sum,
+                &self
+                    .group
+                    .opinv(&prefix(&self.group, tree, r), &prefix(&self.group, tree, l)),
+            );
+            i &= i - 1;
+        }
+        sum
+    }
+
+    /// x座標が`xs`に, y座標が`ys`に含まれる点の総和を計算する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log² *N*)
+    #[must_use]
+    pub fn sum(&self, xs: std::ops::Range<K>, ys: std::ops::Range<K>) -> G::T {
+        if xs.end <= xs.start || ys.end <= ys.start {
+            return self.group.e();
+        }
+        let l = self.xs.partition_point(|&v| v < xs.start);
+        let r = self.xs.partition_point(|&v| v < xs.end);
+        self.group.opinv(&self.prefix(r, &ys), &self.prefix(l, &ys))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monoids::Sum;
+
+    #[test]
+    fn dense() {
+        let mut grid = [[0i64; 7]; 5];
+        let mut bit = BinaryIndexedTree2D::new(Sum::new(), 5, 7);
+        for (i, j, x) in [(1, 2, 3), (4, 6, -2), (0, 0, 5), (1, 2, 1), (3, 4, 7)] {
+            bit.add(i, j, &x);
+            grid[i][j] += x;
+        }
+        for t in 0..=5 {
+            for b in t..=5 {
+                for l in 0..=7 {
+                    for r in l..=7 {
+                        let expected: i64 = grid[t..b].iter().flat_map(|row| &row[l..r]).sum();
+                        assert_eq!(bit.sum(t..b, l..r), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn offline() {
+        let points = (0..40i64)
+            .map(|i| ((i * 37 % 11) * 100_000_000, (i * 13 % 7) * 100_000_000 - 5))
+            .collect::<Vec<_>>();
+        let mut bit = OfflineBinaryIndexedTree2D::new(Sum::new(), points.iter().copied());
+        let mut added = vec![];
+        for (k, &(x, y)) in points.iter().enumerate().step_by(3) {
+            bit.add(x, y, &(k as i64));
+            added.push((x, y, k as i64));
+        }
+        let coords = [-1, 0, 150_000_000, 500_000_000, 999_999_999, 1_000_000_001];
+        for &x0 in &coords {
+            for &x1 in &coords {
+                for &y0 in &coords {
+                    for &y1 in &coords {
+                        let expected: i64 = added
+                            .iter()
+                            .filter(|p| (x0..x1).contains(&p.0) && (y0..y1).contains(&p.1))
+                            .map(|p| p.2)
+                            .sum();
+                        assert_eq!(bit.sum(x0..x1, y0..y1), expected);
+                    }
+                }
+            }
+        }
+    }
+}
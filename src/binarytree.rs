@@ -1,3 +1,4 @@
+use super::util::to_range;
 use std::mem::ManuallyDrop;
 
 union RawBinaryTree<T> {
@@ -152,6 +153,103 @@ impl<T> RawBinaryTree<T> {
         }
     }
 
+    /// 全ての子孫が釣り合った長さ`len`の木の根を, 釣り合うまで回転する
+    ///
+    /// 回転で作り直された子も再帰的に釣り合わせる
+    unsafe fn balance(&mut self, len: usize) {
+        if len <= 1 {
+            return;
+        }
+        loop {
+            let l = self.trunc.0;
+            if l < 3 * (len - l) && len - l < 3 * l {
+                return;
+            }
+            self.rebalance(len);
+            let l = self.trunc.0;
+            let root = (*self.trunc).as_mut();
+            root.1.balance(l);
+            root.2.balance(len - l);
+        }
+    }
+
+    fn new_trunc(left: Self, llen: usize, right: Self) -> Self {
+        Self {
+            trunc: ManuallyDrop::new(Box::new((llen, left, right))),
+        }
+    }
+
+    unsafe fn into_trunc(self) -> (usize, Self, Self) {
+        *ManuallyDrop::into_inner(self.trunc)
+    }
+
+    /// `items`から完全にバランスした木を作る
+    fn build(items: &mut impl Iterator<Item = T>, len: usize) -> Self {
+        if len == 1 {
+            return Self {
+                leaf: ManuallyDrop::new(Box::new(items.next().unwrap())),
+            };
+        }
+        let llen = len / 2;
+        let left = Self::build(items, llen);
+        let right = Self::build(items, len - llen);
+        Self::new_trunc(left, llen, right)
+    }
+
+    /// 長さ`llen`の木と長さ`rlen`の木を繋げる
+    unsafe fn join(left: Self, llen: usize, right: Self, rlen: usize) -> Self {
+        if llen == 0 {
+            return right;
+        }
+        if rlen == 0 {
+            return left;
+        }
+        if llen >= 3 * rlen {
+            let (lsize, a, b) = left.into_trunc();
+            let b = Self::join(b, llen - lsize, right, rlen);
+            let mut r = Self::new_trunc(a, lsize, b);
+            r.balance(llen + rlen);
+            r
+        } else if rlen >= 3 * llen {
+            let (lsize, a, b) = right.into_trunc();
+            let a = Self::join(left, llen, a, lsize);
+            let mut r = Self::new_trunc(a, llen + lsize, b);
+            r.balance(llen + rlen);
+            r
+        } else {
+            Self::new_trunc(left, llen, right)
+        }
+    }
+
+    /// 長さ`len`の木を先頭`index`個とそれ以外に分ける
+    ///
+    /// `0 < index < len`である必要がある
+    unsafe fn split(self, len: usize, index: usize) -> (Self, Self) {
+        let (lsize, a, b) = self.into_trunc();
+        match index.cmp(&lsize) {
+            std::cmp::Ordering::Equal => (a, b),
+            std::cmp::Ordering::Less => {
+                let (x, y) = a.split(lsize, index);
+                (x, Self::join(y, lsize - index, b, len - lsize))
+            }
+            std::cmp::Ordering::Greater => {
+                let (x, y) = b.split(len - lsize, index - lsize);
+                (Self::join(a, lsize, x, index - lsize), y)
+            }
+        }
+    }
+
+    /// 長さ`len`の木を分解して, 値を順に`out`に追加する
+    unsafe fn into_vec(self, len: usize, out: &mut Vec<T>) {
+        if len == 1 {
+            out.push(*ManuallyDrop::into_inner(self.leaf));
+        } else {
+            let (lsize, a, b) = self.into_trunc();
+            a.into_vec(lsize, out);
+            b.into_vec(len - lsize, out);
+        }
+    }
+
     pub unsafe fn debug(&self, len: usize, f: &mut std::fmt::DebugList<'_, '_>)
     where
         T: std::fmt::Debug,
@@ -253,6 +351,171 @@ impl<T> BinaryTree<T> {
             }
         }
     }
+
+    /// 末尾に値を追加する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn push_back(&mut self, item: T) {
+        self.insert(self.len, item);
+    }
+
+    /// 先頭に値を追加する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn push_front(&mut self, item: T) {
+        self.insert(0, item);
+    }
+
+    /// 末尾の値を削除して, その値を返す. 空の場合は`None`を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn pop_back(&mut self) -> Option<T> {
+        (!self.is_empty()).then(|| self.remove(self.len - 1))
+    }
+
+    /// 先頭の値を削除して, その値を返す. 空の場合は`None`を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn pop_front(&mut self) -> Option<T> {
+        (!self.is_empty()).then(|| self.remove(0))
+    }
+
+    /// 位置`at`で2つに分け, `at`以降の要素を返す
+    ///
+    /// # Constraints
+    ///
+    /// - `at <= self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    #[must_use]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len());
+        let len = self.len;
+        if at == len {
+            return Self::new();
+        }
+        let root = std::mem::take(&mut self.root);
+        self.len = 0;
+        if at == 0 {
+            return Self { len, root };
+        }
+        let (l, r) = unsafe { root.split(len, at) };
+        self.root = l;
+        self.len = at;
+        Self {
+            len: len - at,
+            root: r,
+        }
+    }
+
+    /// `other`の要素を全て末尾に移動し, `other`を空にする
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn append(&mut self, other: &mut Self) {
+        let (llen, rlen) = (self.len, other.len);
+        let left = std::mem::take(&mut self.root);
+        let right = std::mem::take(&mut other.root);
+        self.len = 0;
+        other.len = 0;
+        self.root = unsafe { RawBinaryTree::join(left, llen, right, rlen) };
+        self.len = llen + rlen;
+    }
+
+    /// 指定した区間の要素を取り除き, それらを順に返すイテレータを返す
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*k* + log *n*) (*k*は区間の長さ)
+    pub fn drain(&mut self, range: impl std::ops::RangeBounds<usize>) -> std::vec::IntoIter<T> {
+        let (left, right) = to_range(range, self.len);
+        let mut rest = self.split_off(right);
+        let mid = self.split_off(left);
+        self.append(&mut rest);
+        mid.into_iter()
+    }
+
+    /// 要素を先頭から順に返すイテレータを返す
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &T> + use<'_, T> {
+        struct Iter<'a, T>(Vec<(&'a RawBinaryTree<T>, usize)>, usize);
+        impl<'a, T> Iterator for Iter<'a, T> {
+            type Item = &'a T;
+            fn next(&mut self) -> Option<&'a T> {
+                let (mut node, mut len) = self.0.pop()?;
+                self.1 -= 1;
+                unsafe {
+                    while len > 1 {
+                        let trunc = node.trunc.as_ref();
+                        self.0.push((&trunc.2, len - trunc.0));
+                        node = &trunc.1;
+                        len = trunc.0;
+                    }
+                    Some(node.leaf.as_ref())
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.1, Some(self.1))
+            }
+        }
+        impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+        let stack = if self.is_empty() {
+            vec![]
+        } else {
+            vec![(&self.root, self.len)]
+        };
+        Iter(stack, self.len)
+    }
+
+    /// 要素の可変参照を先頭から順に返すイテレータを返す
+    pub fn iter_mut(&mut self) -> impl ExactSizeIterator<Item = &mut T> + use<'_, T> {
+        struct IterMut<'a, T>(Vec<(&'a mut RawBinaryTree<T>, usize)>, usize);
+        impl<'a, T> Iterator for IterMut<'a, T> {
+            type Item = &'a mut T;
+            fn next(&mut self) -> Option<&'a mut T> {
+                let (mut node, mut len) = self.0.pop()?;
+                self.1 -= 1;
+                unsafe {
+                    while len > 1 {
+                        let trunc = node.trunc.as_mut();
+                        self.0.push((&mut trunc.2, len - trunc.0));
+                        node = &mut trunc.1;
+                        len = trunc.0;
+                    }
+                    Some(node.leaf.as_mut())
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.1, Some(self.1))
+            }
+        }
+        impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+        let len = self.len;
+        let stack = if len == 0 {
+            vec![]
+        } else {
+            vec![(&mut self.root, len)]
+        };
+        IterMut(stack, len)
+    }
 }
 
 impl<T> std::ops::Index<usize> for BinaryTree<T> {
@@ -280,6 +543,42 @@ impl<T> Drop for BinaryTree<T> {
     }
 }
 
+impl<T> FromIterator<T> for BinaryTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let vec: Vec<T> = iter.into_iter().collect();
+        let len = vec.len();
+        if len == 0 {
+            return Self::new();
+        }
+        Self {
+            len,
+            root: RawBinaryTree::build(&mut vec.into_iter(), len),
+        }
+    }
+}
+
+impl<T> Extend<T> for BinaryTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.append(&mut iter.into_iter().collect());
+    }
+}
+
+impl<T> IntoIterator for BinaryTree<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let mut vec = Vec::with_capacity(self.len);
+        if self.len > 0 {
+            let len = std::mem::take(&mut self.len);
+            unsafe {
+                std::mem::take(&mut self.root).into_vec(len, &mut vec);
+            }
+        }
+        vec.into_iter()
+    }
+}
+
 impl<T> Default for BinaryTree<T> {
     fn default() -> Self {
         Self::new()
@@ -319,4 +618,95 @@ mod tests {
         assert_eq!(bs.remove(0), 4);
         assert_eq!(bs.len(), 0);
     }
+
+    #[test]
+    fn split_append() {
+        let mut bs = (0..100).collect::<BinaryTree<i32>>();
+        let mut v = (0..100).collect::<Vec<i32>>();
+        for i in 0..100 {
+            let at = i * 37 % (bs.len() + 1);
+            let mut r = bs.split_off(at);
+            let mut w = v.split_off(at);
+            assert!(bs.iter().eq(v.iter()));
+            assert!(r.iter().eq(w.iter()));
+            r.push_front(i as i32);
+            w.insert(0, i as i32);
+            r.append(&mut bs);
+            w.append(&mut v);
+            bs = r;
+            v = w;
+            assert_eq!(bs.len(), v.len());
+        }
+        for x in bs.iter_mut() {
+            *x *= 2;
+        }
+        for x in &mut v {
+            *x *= 2;
+        }
+        assert_eq!(
+            bs.drain(20..50).collect::<Vec<_>>(),
+            v.drain(20..50).collect::<Vec<_>>()
+        );
+        bs.push_back(-1);
+        v.push(-1);
+        assert_eq!(bs.pop_front(), Some(v.remove(0)));
+        assert_eq!(bs.pop_back(), v.pop());
+        for (i, x) in v.iter().enumerate() {
+            assert_eq!(bs[i], *x);
+        }
+        bs.extend(0..10);
+        v.extend(0..10);
+        assert_eq!(bs.into_iter().collect::<Vec<_>>(), v);
+    }
+
+    /// 全てのノードで子の大きさの比が3未満であることを確かめる
+    fn check<T>(tree: &RawBinaryTree<T>, len: usize) {
+        if len > 1 {
+            let (l, left, right) = unsafe { tree.trunc.as_ref() };
+            let r = len - l;
+            assert!(*l < 3 * r && r < 3 * l, "unbalanced: {l} vs {r}");
+            check(left, *l);
+            check(right, r);
+        }
+    }
+
+    #[test]
+    fn balanced_after_split_append() {
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut rand = |n: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % n as u64) as usize
+        };
+        let mut bs = (0..500).collect::<BinaryTree<usize>>();
+        let mut v = (0..500).collect::<Vec<usize>>();
+        for _ in 0..1000 {
+            match rand(3) {
+                0 => {
+                    let at = rand(bs.len() + 1);
+                    let mut r = bs.split_off(at);
+                    let w = v.split_off(at);
+                    check(&bs.root, bs.len());
+                    check(&r.root, r.len());
+                    r.append(&mut bs);
+                    bs = r;
+                    v = w.into_iter().chain(v).collect();
+                }
+                1 => {
+                    let mut other = (0..rand(300)).collect::<BinaryTree<usize>>();
+                    v.extend(0..other.len());
+                    bs.append(&mut other);
+                }
+                _ => {
+                    let l = rand(bs.len() + 1);
+                    let r = l + rand(bs.len() - l + 1).min(50);
+                    assert!(bs.drain(l..r).eq(v.drain(l..r)));
+                }
+            }
+            check(&bs.root, bs.len());
+            assert_eq!(bs.len(), v.len());
+        }
+        assert!(bs.iter().eq(v.iter()));
+    }
 }
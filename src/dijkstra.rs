@@ -13,7 +13,7 @@ impl<T: Radix> PartialEq for DijkstraItem<T> {
 }
 impl<T: Radix> PartialOrd for DijkstraItem<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 impl<T: Radix> Eq for DijkstraItem<T> {}
@@ -0,0 +1,127 @@
+use super::util::{to_range, Associativity, Identity};
+
+/// 区間和クエリなどを定数時間で処理できるデータ構造
+///
+/// SparseTableと異なり, 演算に冪等性を必要としない
+#[derive(Clone)]
+pub struct DisjointSparseTable<M: Associativity<T: Clone>>(Box<[M::T]>, usize, M);
+
+impl<M: Associativity<T: Clone>> DisjointSparseTable<M> {
+    /// 列からDisjointSparseTableを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*N* log *N*)
+    #[must_use]
+    pub fn new(monoid: M, items: impl Into<Vec<M::T>>) -> Self {
+        let items: Vec<M::T> = items.into();
+        let len = items.len();
+        let log = (usize::BITS - len.saturating_sub(1).leading_zeros()) as usize;
+        let mut table = Vec::with_capacity(len * (log + 1));
+        table.extend_from_slice(&items);
+        for k in 1..=log {
+            let offset = table.len();
+            table.extend_from_slice(&items);
+            let half = 1 << (k - 1);
+            for c in (half..len).step_by(half * 2) {
+                for i in (c - half..c - 1).rev() {
+                    table[offset + i] = monoid.op(&items[i], &table[offset + i + 1]);
+                }
+                for i in c + 1..len.min(c + half) {
+                    table[offset + i] = monoid.op(&table[offset + i - 1], &items[i]);
+                }
+            }
+        }
+        Self(table.into(), len, monoid)
+    }
+
+    /// 列の長さを返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.1
+    }
+
+    /// 列が空かどうか調べる
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.1 == 0
+    }
+
+    fn query_inner(&self, left: usize, right: usize) -> M::T {
+        let last = right - 1;
+        if left == last {
+            return self.0[left].clone();
+        }
+        let k = (usize::BITS - (left ^ last).leading_zeros()) as usize;
+        let offset = self.1 * k;
+        self.2.op(&self.0[offset + left], &self.0[offset + last])
+    }
+
+    /// `range`の範囲の総積を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる空でない区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn query(&self, range: impl std::ops::RangeBounds<usize>) -> M::T {
+        let (left, right) = to_range(range, self.len());
+        debug_assert!(left < right);
+        self.query_inner(left, right)
+    }
+
+    /// `range`の範囲の総積を計算する. 区間が空の場合は`monoid.e()`を返す
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn prod(&self, range: impl std::ops::RangeBounds<usize>) -> M::T
+    where
+        M: Identity,
+    {
+        let (left, right) = to_range(range, self.len());
+        if left == right {
+            return self.2.e();
+        }
+        self.query_inner(left, right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monoids::Affine;
+
+    #[test]
+    fn affine() {
+        let items = (0..37).map(|i: i64| (i % 5 - 2, i % 3)).collect::<Vec<_>>();
+        let table = DisjointSparseTable::new(Affine::new(), items.clone());
+        for l in 0..=items.len() {
+            for r in l..=items.len() {
+                let expected = items[l..r]
+                    .iter()
+                    .fold((1, 0), |(a, b), &(c, d)| (a * c, b * c + d));
+                assert_eq!(table.prod(l..r), expected);
+                if l < r {
+                    assert_eq!(table.query(l..r), expected);
+                }
+            }
+        }
+    }
+}
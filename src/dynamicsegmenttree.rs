@@ -0,0 +1,519 @@
+use super::lazysegtree::LazyMonoid;
+use super::segmenttree::Monoid;
+
+/// 区間`range`を, `left`からの相対位置の半開区間に変換する
+fn relative(left: i64, right: i64, range: impl std::ops::RangeBounds<i64>) -> (u128, u128) {
+    let l = match range.start_bound() {
+        std::ops::Bound::Included(&i) => i as i128,
+        std::ops::Bound::Excluded(&i) => i as i128 + 1,
+        std::ops::Bound::Unbounded => left as i128,
+    };
+    let r = match range.end_bound() {
+        std::ops::Bound::Included(&i) => i as i128 + 1,
+        std::ops::Bound::Excluded(&i) => i as i128,
+        std::ops::Bound::Unbounded => right as i128,
+    };
+    debug_assert!(left as i128 <= l && l <= r && r <= right as i128);
+    ((l - left as i128) as u128, (r - left as i128) as u128)
+}
+
+/// 区間の長さ`len`を覆う完全二分木の高さを返す
+fn height(len: u128) -> u32 {
+    len.max(1).next_power_of_two().trailing_zeros()
+}
+
+/// 動的セグメントツリー
+///
+/// 巨大な添字の範囲に対して, 必要になったノードだけを作るセグメントツリー.
+/// 値を設定していない位置は`monoid.e()`として扱われる.
+pub struct DynamicSegmentTree<M: Monoid> {
+    /// (値, 子ノードの番号). 子ノードの番号が0なら子が無いことを表す
+    nodes: Vec<(M::T, [usize; 2])>,
+    left: i64,
+    right: i64,
+    log: u32,
+    e: M::T,
+    monoid: M,
+}
+
+impl<M: Monoid> DynamicSegmentTree<M> {
+    /// 添字の範囲が`range`のDynamicSegmentTreeを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn new(monoid: M, range: std::ops::Range<i64>) -> Self {
+        Self::with_capacity(monoid, range, 1)
+    }
+
+    /// 添字の範囲が`range`で, ノード`capacity`個分の領域を予め確保したDynamicSegmentTreeを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn with_capacity(monoid: M, range: std::ops::Range<i64>, capacity: usize) -> Self {
+        debug_assert!(range.start <= range.end);
+        let mut nodes = Vec::with_capacity(capacity.max(1));
+        let e = monoid.e();
+        nodes.push((e.clone(), [0, 0]));
+        Self {
+            nodes,
+            left: range.start,
+            right: range.end,
+            log: height((range.end as i128 - range.start as i128) as u128),
+            e,
+            monoid,
+        }
+    }
+
+    /// 作られたノードの数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn val(&self, k: usize) -> &M::T {
+        if k == 0 {
+            &self.e
+        } else {
+            &self.nodes[k].0
+        }
+    }
+
+    /// `index`番目の値を`item`に設定する
+    ///
+    /// # Constraints
+    ///
+    /// - `index`は添字の範囲に含まれる
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*) (*N*は添字の範囲の大きさ)
+    pub fn set(&mut self, index: i64, item: M::T) {
+        debug_assert!(self.left <= index && index < self.right);
+        let pos = (index as i128 - self.left as i128) as u128;
+        let mut path = Vec::with_capacity(self.log as usize);
+        let mut k = 0;
+        for level in (0..self.log).rev() {
+            path.push(k);
+            let b = (pos >> level) as usize & 1;
+            if self.nodes[k].1[b] == 0 {
+                self.nodes.push((self.e.clone(), [0, 0]));
+                self.nodes[k].1[b] = self.nodes.len() - 1;
+            }
+            k = self.nodes[k].1[b];
+        }
+        self.nodes[k].0 = item;
+        for k in path.into_iter().rev() {
+            let [l, r] = self.nodes[k].1;
+            self.nodes[k].0 = self.monoid.op(self.val(l), self.val(r));
+        }
+    }
+
+    /// `index`番目の値を取得する
+    ///
+    /// # Constraints
+    ///
+    /// - `index`は添字の範囲に含まれる
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn get(&self, index: i64) -> &M::T {
+        debug_assert!(self.left <= index && index < self.right);
+        let pos = (index as i128 - self.left as i128) as u128;
+        let mut k = 0;
+        for level in (0..self.log).rev() {
+            k = self.nodes[k].1[(pos >> level) as usize & 1];
+            if k == 0 {
+                return &self.e;
+            }
+        }
+        &self.nodes[k].0
+    }
+
+    /// `range`の範囲の総積を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は添字の範囲に含まれる区間である
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn prod(&self, range: impl std::ops::RangeBounds<i64>) -> M::T {
+        fn internal<M: Monoid>(
+            seg: &DynamicSegmentTree<M>,
+            k: usize,
+            level: u32,
+            node_left: u128,
+            (l, r): (u128, u128),
+        ) -> M::T {
+            let node_right = node_left + (1 << level);
+            if r <= node_left || node_right <= l {
+                return seg.monoid.e();
+            }
+            if l <= node_left && node_right <= r {
+                return seg.nodes[k].0.clone();
+            }
+            let [lc, rc] = seg.nodes[k].1;
+            let mid = node_left + (1 << (level - 1));
+            let lv = if lc == 0 {
+                seg.monoid.e()
+            } else {
+                internal(seg, lc, level - 1, node_left, (l, r))
+            };
+            let rv = if rc == 0 {
+                seg.monoid.e()
+            } else {
+                internal(seg, rc, level - 1, mid, (l, r))
+            };
+            seg.monoid.op(&lv, &rv)
+        }
+
+        let (l, r) = relative(self.left, self.right, range);
+        if l == r {
+            return self.monoid.e();
+        }
+        internal(self, 0, self.log, 0, (l, r))
+    }
+
+    /// `f(self.prod(left..x))`が`true`となる最大の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(self.prod(left..x))`かつ (`x`が添字の範囲の右端または`!f(self.prod(left..=x))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `left`は添字の範囲に含まれるか, 範囲の右端である
+    /// - `f(monoid.e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn max_right(&self, left: i64, f: impl Fn(&M::T) -> bool) -> i64 {
+        fn internal<M: Monoid>(
+            seg: &DynamicSegmentTree<M>,
+            k: usize,
+            level: u32,
+            node_left: u128,
+            left: u128,
+            acc: &mut M::T,
+            f: &impl Fn(&M::T) -> bool,
+        ) -> Option<u128> {
+            let node_right = node_left + (1 << level);
+            if node_right <= left {
+                return None;
+            }
+            if left <= node_left {
+                let temp = seg.monoid.op(acc, &seg.nodes[k].0);
+                if f(&temp) {
+                    *acc = temp;
+                    return None;
+                }
+                if level == 0 {
+                    return Some(node_left);
+                }
+            }
+            let mid = node_left + (1 << (level - 1));
+            for (c, node_left) in seg.nodes[k].1.into_iter().zip([node_left, mid]) {
+                if c != 0 {
+                    if let Some(x) = internal(seg, c, level - 1, node_left, left, acc, f) {
+                        return Some(x);
+                    }
+                }
+            }
+            None
+        }
+
+        debug_assert!(self.left <= left && left <= self.right);
+        let mut acc = self.monoid.e();
+        debug_assert!(f(&acc));
+        let (l, _) = relative(self.left, self.right, left..);
+        match internal(self, 0, self.log, 0, l, &mut acc, &f) {
+            Some(x) => (self.left as i128 + x as i128).min(self.right as i128) as i64,
+            None => self.right,
+        }
+    }
+}
+
+/// 動的遅延伝搬セグメントツリー
+///
+/// 巨大な添字の範囲に対して, 必要になったノードだけを作る遅延伝搬セグメントツリー.
+/// 全ての位置の値は最初は`init`で初期化されている.
+pub struct DynamicLazySegmentTree<M: LazyMonoid> {
+    /// (値, 作用素, 子ノードの番号). 子ノードの番号が0なら子が無いことを表す
+    nodes: Vec<(M::T, M::F, [usize; 2])>,
+    /// `pows[i]`は長さ`2^i`の区間の初期値
+    pows: Vec<M::T>,
+    left: i64,
+    right: i64,
+    monoid: M,
+}
+
+impl<M: LazyMonoid> DynamicLazySegmentTree<M> {
+    /// 添字の範囲が`range`で, 全ての値が`init`のDynamicLazySegmentTreeを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*) (*N*は添字の範囲の大きさ)
+    #[must_use]
+    pub fn new(monoid: M, range: std::ops::Range<i64>, init: M::T) -> Self {
+        Self::with_capacity(monoid, range, init, 1)
+    }
+
+    /// 添字の範囲が`range`で全ての値が`init`の, ノード`capacity`個分の領域を予め確保したDynamicLazySegmentTreeを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn with_capacity(
+        monoid: M,
+        range: std::ops::Range<i64>,
+        init: M::T,
+        capacity: usize,
+    ) -> Self {
+        debug_assert!(range.start <= range.end);
+        let log = height((range.end as i128 - range.start as i128) as u128);
+        let mut pows = vec![init];
+        for i in 0..log as usize {
+            pows.push(monoid.op(&pows[i], &pows[i]));
+        }
+        let mut nodes = Vec::with_capacity(capacity.max(1));
+        nodes.push((pows[log as usize].clone(), monoid.id(), [0, 0]));
+        Self {
+            nodes,
+            pows,
+            left: range.start,
+            right: range.end,
+            monoid,
+        }
+    }
+
+    /// 作られたノードの数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn log(&self) -> u32 {
+        self.pows.len() as u32 - 1
+    }
+
+    fn push(&mut self, k: usize, level: u32) {
+        let f = std::mem::replace(&mut self.nodes[k].1, self.monoid.id());
+        for b in 0..2 {
+            let c = self.nodes[k].2[b];
+            if c == 0 {
+                let val = self.monoid.map(&f, &self.pows[level as usize - 1]);
+                let lazy = self.monoid.comp(&f, &self.monoid.id());
+                self.nodes.push((val, lazy, [0, 0]));
+                self.nodes[k].2[b] = self.nodes.len() - 1;
+            } else {
+                let node = &mut self.nodes[c];
+                node.0 = self.monoid.map(&f, &node.0);
+                node.1 = self.monoid.comp(&f, &node.1);
+            }
+        }
+    }
+
+    fn pull(&mut self, k: usize) {
+        let [l, r] = self.nodes[k].2;
+        self.nodes[k].0 = self.monoid.op(&self.nodes[l].0, &self.nodes[r].0);
+    }
+
+    /// `index`番目の値を`item`に設定する
+    ///
+    /// # Constraints
+    ///
+    /// - `index`は添字の範囲に含まれる
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn set(&mut self, index: i64, item: M::T) {
+        debug_assert!(self.left <= index && index < self.right);
+        let pos = (index as i128 - self.left as i128) as u128;
+        let mut path = Vec::with_capacity(self.log() as usize);
+        let mut k = 0;
+        for level in (1..=self.log()).rev() {
+            path.push(k);
+            self.push(k, level);
+            k = self.nodes[k].2[(pos >> (level - 1)) as usize & 1];
+        }
+        self.nodes[k].0 = item;
+        for k in path.into_iter().rev() {
+            self.pull(k);
+        }
+    }
+
+    /// `index`番目の値を取得する
+    ///
+    /// # Constraints
+    ///
+    /// - `index`は添字の範囲に含まれる
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn get(&mut self, index: i64) -> M::T {
+        self.prod(index..=index)
+    }
+
+    /// `range`の範囲の総積を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は添字の範囲に含まれる区間である
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn prod(&mut self, range: impl std::ops::RangeBounds<i64>) -> M::T {
+        fn internal<M: LazyMonoid>(
+            seg: &mut DynamicLazySegmentTree<M>,
+            k: usize,
+            level: u32,
+            node_left: u128,
+            (l, r): (u128, u128),
+        ) -> M::T {
+            let node_right = node_left + (1 << level);
+            if r <= node_left || node_right <= l {
+                return seg.monoid.e();
+            }
+            if l <= node_left && node_right <= r {
+                return seg.nodes[k].0.clone();
+            }
+            seg.push(k, level);
+            let [lc, rc] = seg.nodes[k].2;
+            let mid = node_left + (1 << (level - 1));
+            let lv = internal(seg, lc, level - 1, node_left, (l, r));
+            let rv = internal(seg, rc, level - 1, mid, (l, r));
+            seg.monoid.op(&lv, &rv)
+        }
+
+        let (l, r) = relative(self.left, self.right, range);
+        if l == r {
+            return self.monoid.e();
+        }
+        internal(self, 0, self.log(), 0, (l, r))
+    }
+
+    /// `range`の範囲に作用素`f`を適用する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は添字の範囲に含まれる区間である
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn apply(&mut self, range: impl std::ops::RangeBounds<i64>, f: &M::F) {
+        fn internal<M: LazyMonoid>(
+            seg: &mut DynamicLazySegmentTree<M>,
+            k: usize,
+            level: u32,
+            node_left: u128,
+            (l, r): (u128, u128),
+            f: &M::F,
+        ) {
+            let node_right = node_left + (1 << level);
+            if r <= node_left || node_right <= l {
+                return;
+            }
+            if l <= node_left && node_right <= r {
+                let node = &mut seg.nodes[k];
+                node.0 = seg.monoid.map(f, &node.0);
+                node.1 = seg.monoid.comp(f, &node.1);
+                return;
+            }
+            seg.push(k, level);
+            let [lc, rc] = seg.nodes[k].2;
+            let mid = node_left + (1 << (level - 1));
+            internal(seg, lc, level - 1, node_left, (l, r), f);
+            internal(seg, rc, level - 1, mid, (l, r), f);
+            seg.pull(k);
+        }
+
+        let (l, r) = relative(self.left, self.right, range);
+        if l == r {
+            return;
+        }
+        internal(self, 0, self.log(), 0, (l, r), f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monoids::{RangeAddRangeSum, Sum};
+
+    #[test]
+    fn sum() {
+        const INF: i64 = 1_000_000_000_000_000_000;
+        let mut seg = DynamicSegmentTree::new(Sum::new(), -INF..INF);
+        seg.set(-INF, 3);
+        seg.set(0, 1);
+        seg.set(12345, 4);
+        seg.set(INF - 1, 1);
+        assert_eq!(seg.prod(..), 9);
+        assert_eq!(seg.prod(-5..=12345), 5);
+        assert_eq!(seg.prod(1..), 5);
+        assert_eq!(seg.prod(7..7), 0);
+        assert_eq!(*seg.get(12345), 4);
+        assert_eq!(*seg.get(12346), 0);
+        assert_eq!(seg.max_right(-INF, |&s| s < 4), 0);
+        assert_eq!(seg.max_right(-INF, |&s| s < 8), 12345);
+        assert_eq!(seg.max_right(1, |&s| s < 5), INF - 1);
+        assert_eq!(seg.max_right(1, |&s| s < 6), INF);
+        assert!(seg.node_count() <= 4 * 61 + 1);
+
+        seg.set(0, 10);
+        assert_eq!(seg.prod(..), 18);
+        assert_eq!(seg.max_right(-INF, |&s| s < 8), 0);
+    }
+
+    #[test]
+    fn add_sum() {
+        let mut seg =
+            DynamicLazySegmentTree::new(RangeAddRangeSum::new(), -5..1_000_000_000, (0, 1));
+        let mut v = [0i64; 30];
+        for (i, (l, r, x)) in [(3, 20, 4), (-5, 10, -2), (0, 1_000_000_000, 1), (7, 8, 100)]
+            .into_iter()
+            .enumerate()
+        {
+            seg.apply(l..r, &x);
+            for (j, v) in v.iter_mut().enumerate() {
+                if l <= j as i64 - 5 && (j as i64 - 5) < r {
+                    *v += x;
+                }
+            }
+            for l in 0..30 {
+                for r in l..30 {
+                    assert_eq!(
+                        seg.prod(l as i64 - 5..r as i64 - 5),
+                        (v[l..r].iter().sum(), (r - l) as i64),
+                        "{i} {l} {r}"
+                    );
+                }
+            }
+        }
+        assert_eq!(seg.prod(..), (1_000_000_000 + 68 - 30 + 100, 1_000_000_005));
+        seg.set(100, (7, 1));
+        assert_eq!(seg.get(100), (7, 1));
+        assert_eq!(seg.get(101), (1, 1));
+    }
+}
@@ -177,7 +177,7 @@ mod tests {
     fn fft_ifft() {
         type Mint = ModInt<998244353>;
         let a = [3, 1, 4, 1, 5, 9, 2, 6].map(Mint::new);
-        let mut b = a.clone();
+        let mut b = a;
         fft(&mut b);
         ifft(&mut b);
         assert_eq!(a, b);
@@ -1,4 +1,5 @@
 use super::segmenttree::Monoid;
+use super::util::{Associativity, Identity, Magma};
 
 pub trait LazyMonoid: Monoid {
     type F;
@@ -8,6 +9,64 @@ pub trait LazyMonoid: Monoid {
     fn map(&self, f: &Self::F, x: &Self::T) -> Self::T;
 }
 
+/// モノイドと作用素の関数から作るLazyMonoid
+///
+/// `comp(g, f)`は`f`を作用させた後に`g`を作用させる作用素を返す必要がある
+#[derive(Clone, Copy)]
+pub struct FnLazyMonoid<M, F, Comp, Map> {
+    monoid: M,
+    id: F,
+    comp: Comp,
+    map: Map,
+}
+
+impl<M: Monoid, F: Clone, Comp, Map> FnLazyMonoid<M, F, Comp, Map>
+where
+    Comp: Fn(&F, &F) -> F,
+    Map: Fn(&F, &M::T) -> M::T,
+{
+    /// モノイド`monoid`と恒等写像`id`, 作用素の合成`comp`, 作用素の適用`map`からLazyMonoidを作る
+    #[must_use]
+    pub fn new(monoid: M, id: F, comp: Comp, map: Map) -> Self {
+        Self {
+            monoid,
+            id,
+            comp,
+            map,
+        }
+    }
+}
+
+impl<M: Magma, F, Comp, Map> Magma for FnLazyMonoid<M, F, Comp, Map> {
+    type T = M::T;
+    fn op(&self, lhs: &M::T, rhs: &M::T) -> M::T {
+        self.monoid.op(lhs, rhs)
+    }
+}
+impl<M: Identity, F, Comp, Map> Identity for FnLazyMonoid<M, F, Comp, Map> {
+    fn e(&self) -> M::T {
+        self.monoid.e()
+    }
+}
+impl<M: Associativity, F, Comp, Map> Associativity for FnLazyMonoid<M, F, Comp, Map> {}
+
+impl<M: Monoid, F: Clone, Comp, Map> LazyMonoid for FnLazyMonoid<M, F, Comp, Map>
+where
+    Comp: Fn(&F, &F) -> F,
+    Map: Fn(&F, &M::T) -> M::T,
+{
+    type F = F;
+    fn id(&self) -> F {
+        self.id.clone()
+    }
+    fn comp(&self, g: &F, f: &F) -> F {
+        (self.comp)(g, f)
+    }
+    fn map(&self, f: &F, x: &M::T) -> M::T {
+        (self.map)(f, x)
+    }
+}
+
 /// 遅延伝搬セグメントツリー
 ///
 /// 特定の条件を満たすクエリの区間更新・区間取得が可能である
@@ -41,7 +100,7 @@ impl<M: LazyMonoid> LazySegmentTree<M> {
             let mut offset = 0;
             while len > 1 {
                 for i in (1..len).step_by(2) {
-                    vec.push(monoid.op(&vec[i + offset], &vec[i + offset - 1]));
+                    vec.push(monoid.op(&vec[i + offset - 1], &vec[i + offset]));
                 }
                 offset += len;
                 len >>= 1;
@@ -98,40 +157,39 @@ impl<M: LazyMonoid> LazySegmentTree<M> {
         }
     }
 
+    /// `level`段目の`index`番目のノードの作用素を子に伝搬する
+    ///
+    /// `offset`は`level`段目の先頭の位置
+    fn push(&mut self, level: u32, offset: usize, index: usize) {
+        let len = self.len();
+        let j = offset - len + index;
+        let k = offset - (len >> (level - 1)) + (index << 1);
+        let f = std::mem::replace(&mut self.1[j], self.2.id());
+        self.0[k] = self.2.map(&f, &self.0[k]);
+        self.0[k + 1] = self.2.map(&f, &self.0[k + 1]);
+        if level > 1 {
+            self.1[k - len] = self.2.comp(&f, &self.1[k - len]);
+            self.1[k + 1 - len] = self.2.comp(&f, &self.1[k + 1 - len]);
+        }
+    }
+
+    /// `level`段目の`index`番目のノードの値を子から再計算する
+    fn pull(&mut self, level: u32, offset: usize, index: usize) {
+        let k = offset - (self.len() >> (level - 1)) + (index << 1);
+        self.0[offset + index] = self.2.op(&self.0[k], &self.0[k + 1]);
+    }
+
+    /// 区間`l..r`の総積の計算に必要なノードの作用素を全て伝搬する
     fn map_range(&mut self, l: usize, r: usize) {
-        let r = r - 1;
         let len = self.len();
-        let mut offset = self.1.len();
-        for i in (1..usize::BITS - len.leading_zeros()).rev() {
-            offset -= len >> i;
-            if len >> i == l >> i {
-                continue;
+        let mut offset = self.0.len();
+        for level in (1..usize::BITS - len.leading_zeros()).rev() {
+            offset -= len >> level;
+            if (l >> level) << level != l && l >> level < len >> level {
+                self.push(level, offset, l >> level);
             }
-            let n_offset = offset + len - (len >> (i - 1));
-            {
-                let j = offset + (l >> i);
-                let k = n_offset + ((l >> (i - 1)) & !1);
-                self.0[k] = self.2.map(&self.1[j], &self.0[k]);
-                self.0[k + 1] = self.2.map(&self.1[j], &self.0[k + 1]);
-                if i > 1 {
-                    self.1[k - len] = self.2.comp(&self.1[j], &self.1[k - len]);
-                    self.1[k + 1 - len] = self.2.comp(&self.1[j], &self.1[k + 1 - len]);
-                }
-                self.1[j] = self.2.id();
-            }
-            if len >> i == r >> i || r >> i == l >> i {
-                continue;
-            }
-            {
-                let j = offset + (r >> i);
-                let k = n_offset + ((r >> (i - 1)) & !1);
-                self.0[k] = self.2.map(&self.1[j], &self.0[k]);
-                self.0[k + 1] = self.2.map(&self.1[j], &self.0[k + 1]);
-                if i > 1 {
-                    self.1[k - len] = self.2.comp(&self.1[j], &self.1[k - len]);
-                    self.1[k + 1 - len] = self.2.comp(&self.1[j], &self.1[k + 1 - len]);
-                }
-                self.1[j] = self.2.id();
+            if (r >> level) << level != r && (r - 1) >> level < len >> level {
+                self.push(level, offset, (r - 1) >> level);
             }
         }
     }
@@ -148,28 +206,18 @@ impl<M: LazyMonoid> LazySegmentTree<M> {
         }
     }
 
+    /// 区間`l..r`に作用素を適用した後に影響を受けるノードの値を再計算する
     fn update_range(&mut self, l: usize, r: usize) {
-        let mut len = self.len();
-        let mut slice = self.0.as_mut();
-        for i in 0.. {
-            if l >> (i + 1) >= len >> 1 {
-                break;
-            }
-            if l >> (i + 1) != ((l - 1) >> (i + 1)) + 1 {
-                slice[len + (l >> (i + 1))] =
-                    self.2.op(&slice[(l >> i) & !1], &slice[(l >> i) | 1]);
+        let len = self.len();
+        let mut offset = 0;
+        for level in 1..usize::BITS - len.leading_zeros() {
+            offset += len >> (level - 1);
+            if (l >> level) << level != l && l >> level < len >> level {
+                self.pull(level, offset, l >> level);
             }
-            if r >> (i + 1) < len >> 1
-                && l >> (i + 1) != r >> (i + 1)
-                && r >> (i + 1) != ((r - 1) >> (r + 1)) + 1
-            {
-                slice[len + ((r - 1) >> (i + 1)) + 1] = self.2.op(
-                    &slice[(((r - 1) >> i) + 2) & !1],
-                    &slice[(((r - 1) >> i) + 2) | 1],
-                );
+            if (r >> level) << level != r && (r - 1) >> level < len >> level {
+                self.pull(level, offset, (r - 1) >> level);
             }
-            slice = &mut slice[len..];
-            len >>= 1;
         }
     }
 
@@ -375,6 +423,147 @@ impl<M: LazyMonoid> LazySegmentTree<M> {
         }
         self.update_range(left, right);
     }
+
+    /// `f(self.prod(left..x))`が`true`となる最大の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(self.prod(left..x))`かつ (`x == self.len()`または`!f(self.prod(left..=x))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `left <= self.len()`
+    /// - `f(monoid.e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn max_right(&mut self, left: usize, f: impl Fn(&M::T) -> bool) -> usize {
+        let len = self.len();
+        debug_assert!(left <= len);
+        debug_assert!(f(&self.2.e()));
+        if left == len {
+            return len;
+        }
+        self.map_range(left, left + 1);
+        let mut index = left;
+        let mut sh = 0;
+        let mut offset = 0;
+        let mut r = self.2.e();
+        while index < len >> sh {
+            while index & 1 == 0 && len >> sh > 1 {
+                index >>= 1;
+                offset += len >> sh;
+                sh += 1;
+            }
+            if index == len >> sh {
+                break;
+            }
+            let temp = self.2.op(&r, &self.0[index + offset]);
+            if !f(&temp) {
+                break;
+            }
+            r = temp;
+            index += 1;
+        }
+        while sh > 0 {
+            if index < len >> sh {
+                self.push(sh, offset, index);
+            }
+            sh -= 1;
+            offset -= len >> sh;
+            index <<= 1;
+            if index == len >> sh {
+                continue;
+            }
+            let temp = self.2.op(&r, &self.0[index + offset]);
+            if f(&temp) {
+                r = temp;
+                index += 1;
+            }
+        }
+        index
+    }
+
+    /// `f(self.prod(x..right))`が`true`となる最小の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(self.prod(x..right))`かつ (`x == 0`または`!f(self.prod(x - 1..right))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `right <= self.len()`
+    /// - `f(monoid.e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn min_left(&mut self, right: usize, f: impl Fn(&M::T) -> bool) -> usize {
+        let len = self.len();
+        debug_assert!(right <= len);
+        debug_assert!(f(&self.2.e()));
+        if right == 0 {
+            return 0;
+        }
+        self.map_range(right - 1, right);
+        let mut index = right;
+        let mut sh = 0;
+        let mut offset = 0;
+        let mut r = self.2.e();
+        while index > 0 {
+            while index & 1 == 0 {
+                index >>= 1;
+                offset += len >> sh;
+                sh += 1;
+            }
+            let temp = self.2.op(&self.0[index - 1 + offset], &r);
+            if !f(&temp) {
+                break;
+            }
+            r = temp;
+            index -= 1;
+        }
+        if index == 0 {
+            return 0;
+        }
+        while sh > 0 {
+            self.push(sh, offset, index - 1);
+            sh -= 1;
+            offset -= len >> sh;
+            index <<= 1;
+            let temp = self.2.op(&self.0[index - 1 + offset], &r);
+            if f(&temp) {
+                r = temp;
+                index -= 1;
+            }
+        }
+        index
+    }
+
+    /// 全ての作用素を伝搬して, 列の要素を順に返すイテレータを返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*n*)
+    pub fn iter(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &M::T> + ExactSizeIterator + use<'_, M> {
+        let len = self.len();
+        let mut offset = self.0.len();
+        for level in (1..usize::BITS - len.leading_zeros()).rev() {
+            offset -= len >> level;
+            for i in 0..len >> level {
+                self.push(level, offset, i);
+            }
+        }
+        self.0[..len].iter()
+    }
+
+    /// 全ての作用素を伝搬して, 列を`Vec`にして返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*n*)
+    pub fn to_vec(&mut self) -> Vec<M::T> {
+        self.iter().cloned().collect()
+    }
 }
 
 #[cfg(test)]
@@ -436,4 +625,123 @@ mod tests {
         assert_eq!(seg.prod(2..7), (-16, 14));
         assert_eq!(seg.prod(5..), (-15, 9));
     }
+
+    #[test]
+    fn add_sum_brute() {
+        // 区間加算・区間和 (値は(総和, 長さ))
+        struct TestMonoid;
+        impl super::super::util::Magma for TestMonoid {
+            type T = (i64, i64);
+            fn op(&self, &a: &(i64, i64), &b: &(i64, i64)) -> (i64, i64) {
+                (a.0 + b.0, a.1 + b.1)
+            }
+        }
+        impl super::super::util::Identity for TestMonoid {
+            fn e(&self) -> (i64, i64) {
+                (0, 0)
+            }
+        }
+        impl super::super::util::Associativity for TestMonoid {}
+        impl LazyMonoid for TestMonoid {
+            type F = i64;
+            fn id(&self) -> i64 {
+                0
+            }
+            fn comp(&self, &g: &i64, &f: &i64) -> i64 {
+                g + f
+            }
+            fn map(&self, &f: &i64, &x: &(i64, i64)) -> (i64, i64) {
+                (x.0 + f * x.1, x.1)
+            }
+        }
+
+        for n in 1..20 {
+            let mut v = (0..n as i64).collect::<Vec<_>>();
+            let mut seg =
+                LazySegmentTree::from_vec(TestMonoid, v.iter().map(|&x| (x, 1)).collect());
+            for t in 0..200 {
+                let l = t * 7 % n;
+                let r = l + 1 + t * 11 % (n - l);
+                let f = t as i64 % 5 - 2;
+                seg.apply(l..r, &f);
+                for x in &mut v[l..r] {
+                    *x += f;
+                }
+                let (a, b) = (t * 3 % n, t * 13 % n);
+                let (a, b) = (a.min(b), a.max(b) + 1);
+                assert_eq!(seg.prod(a..b).0, v[a..b].iter().sum::<i64>());
+            }
+        }
+    }
+
+    #[test]
+    fn from_vec_non_commutative() {
+        use super::super::util::FnMonoid;
+
+        let items = ["a", "b", "c", "d", "e", "f", "g"].map(String::from);
+        let monoid = FnLazyMonoid::new(
+            FnMonoid::new(String::new(), |a: &String, b: &String| a.clone() + b),
+            (),
+            |_: &(), _: &()| (),
+            |_: &(), x: &String| x.clone(),
+        );
+        let mut seg = LazySegmentTree::from_vec(monoid, items.to_vec());
+        for l in 0..=items.len() {
+            for r in l..=items.len() {
+                assert_eq!(seg.prod(l..r), items[l..r].concat());
+            }
+        }
+    }
+
+    #[test]
+    fn fn_lazy_monoid() {
+        use super::super::util::FnMonoid;
+
+        // 区間アフィン変換・区間和
+        let monoid = FnLazyMonoid::new(
+            FnMonoid::new((0.0, 0.0), |a: &(f64, f64), b: &(f64, f64)| {
+                (a.0 + b.0, a.1 + b.1)
+            }),
+            (1.0, 0.0),
+            |g: &(f64, f64), f: &(f64, f64)| (g.0 * f.0, g.0 * f.1 + g.1),
+            |f: &(f64, f64), x: &(f64, f64)| (f.0 * x.0 + f.1 * x.1, x.1),
+        );
+        let mut seg = LazySegmentTree::from_vec(monoid, (0..8).map(|i| (i as f64, 1.0)).collect());
+        assert_eq!(seg.prod(2..5).0, 9.0);
+        seg.apply(1..6, &(2.0, 0.5));
+        assert_eq!(seg.prod(2..5).0, 19.5);
+        seg.apply(4.., &(0.5, -1.0));
+        assert_eq!(seg.prod(..).0, 25.5);
+    }
+
+    #[test]
+    fn max_right_min_left() {
+        use super::super::monoids::RangeAddRangeSum;
+
+        let mut v = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9];
+        let mut seg =
+            LazySegmentTree::from_vec(RangeAddRangeSum::new(), v.iter().map(|&x| (x, 1)).collect());
+        let check = |seg: &mut LazySegmentTree<_>, v: &[i32]| {
+            for i in 0..=v.len() {
+                for k in (0..80).step_by(3) {
+                    let r = (i..=v.len())
+                        .rev()
+                        .find(|&r| v[i..r].iter().sum::<i32>() <= k);
+                    assert_eq!(Some(seg.max_right(i, |&(s, _)| s <= k)), r);
+                    let l = (0..=i).find(|&l| v[l..i].iter().sum::<i32>() <= k);
+                    assert_eq!(Some(seg.min_left(i, |&(s, _)| s <= k)), l);
+                }
+            }
+        };
+        check(&mut seg, &v);
+        for (range, f) in [(2..7, 3), (0..4, 1), (5..13, 2), (8..9, 10)] {
+            seg.apply(range.clone(), &f);
+            for x in &mut v[range] {
+                *x += f;
+            }
+            check(&mut seg, &v);
+        }
+        assert_eq!(seg.to_vec(), v.iter().map(|&x| (x, 1)).collect::<Vec<_>>());
+        assert_eq!(seg.iter().next_back(), Some(&(11, 1)));
+    }
 }
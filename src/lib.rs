@@ -1,20 +1,30 @@
 pub mod binaryindexedtree;
+pub mod binaryindexedtree2d;
 pub mod binarytree;
 pub mod dijkstra;
+pub mod disjointsparsetable;
+pub mod dynamicsegmenttree;
 pub mod fft;
 pub mod kruskal;
 pub mod lazysegtree;
+pub mod lichaotree;
+pub mod linearrmq;
 pub mod mastertree;
 pub mod mo;
 pub mod modint;
+pub mod monoids;
 pub mod persistentarray;
+pub mod persistentlazysegtree;
 pub mod persistentsegmenttree;
 pub mod persistentunionfind;
+pub mod prefixsum2d;
 pub mod radixheap;
 pub mod rangeset;
 pub mod rollinghash;
 pub mod scc;
 pub mod segmenttree;
+pub mod segmenttreebeats;
+pub mod sortedmultiset;
 pub mod sparsetable;
 pub mod topologicalsort;
 pub mod unionfind;
@@ -0,0 +1,369 @@
+use std::collections::VecDeque;
+
+/// 直線`y = a x + b`を`(a, b)`で表したもの
+type Line = (i64, i64);
+
+/// 直線`line`の`x`での値
+fn eval((a, b): Line, x: i64) -> i64 {
+    a * x + b
+}
+
+/// LiChaoTree
+///
+/// 整数の区間上で, 直線や線分の追加と, ある点での最小値(または最大値)の取得を処理する.
+/// 必要になったノードだけを作るため, 巨大な区間も扱える.
+#[derive(Clone)]
+pub struct LiChaoTree {
+    /// (直線, 子ノードの番号). 子ノードの番号が0なら子が無いことを表す
+    nodes: Vec<(Option<Line>, [usize; 2])>,
+    left: i64,
+    right: i64,
+    max: bool,
+}
+
+impl LiChaoTree {
+    fn new(range: std::ops::Range<i64>, max: bool) -> Self {
+        debug_assert!(range.start < range.end);
+        Self {
+            nodes: vec![(None, [0, 0])],
+            left: range.start,
+            right: range.end,
+            max,
+        }
+    }
+
+    /// `range`上で最小値を求めるLiChaoTreeを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn new_min(range: std::ops::Range<i64>) -> Self {
+        Self::new(range, false)
+    }
+
+    /// `range`上で最大値を求めるLiChaoTreeを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn new_max(range: std::ops::Range<i64>) -> Self {
+        Self::new(range, true)
+    }
+
+    /// 作られたノードの数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn child(&mut self, k: usize, b: usize) -> usize {
+        if self.nodes[k].1[b] == 0 {
+            self.nodes.push((None, [0, 0]));
+            self.nodes[k].1[b] = self.nodes.len() - 1;
+        }
+        self.nodes[k].1[b]
+    }
+
+    /// 区間`l..r`を担当するノード`k`に直線`line`を追加する
+    fn add(&mut self, mut k: usize, mut l: i64, mut r: i64, mut line: Line) {
+        loop {
+            let Some(cur) = self.nodes[k].0.as_mut() else {
+                self.nodes[k].0 = Some(line);
+                return;
+            };
+            let m = l + (r - l) / 2;
+            if eval(line, m) < eval(*cur, m) {
+                std::mem::swap(cur, &mut line);
+            }
+            let cur = *cur;
+            if r - l == 1 {
+                return;
+            }
+            if eval(line, l) < eval(cur, l) {
+                k = self.child(k, 0);
+                r = m;
+            } else if eval(line, r - 1) < eval(cur, r - 1) {
+                k = self.child(k, 1);
+                l = m;
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// 直線`y = a x + b`を追加する
+    ///
+    /// # Constraints
+    ///
+    /// - 区間内の全ての`x`で`a x + b`がオーバーフローしない.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*) (*N*は区間の大きさ)
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        let line = if self.max { (-a, -b) } else { (a, b) };
+        self.add(0, self.left, self.right, line);
+    }
+
+    /// 線分`y = a x + b` (`x`は`range`に含まれる) を追加する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は区間に含まれる.
+    /// - 区間内の全ての`x`で`a x + b`がオーバーフローしない.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log² *N*) (*N*は区間の大きさ)
+    pub fn add_segment(&mut self, range: std::ops::Range<i64>, a: i64, b: i64) {
+        debug_assert!(self.left <= range.start && range.end <= self.right);
+        let line = if self.max { (-a, -b) } else { (a, b) };
+        let mut stack = vec![(0, self.left, self.right)];
+        while let Some((k, l, r)) = stack.pop() {
+            if range.end <= l || r <= range.start {
+                continue;
+            }
+            if range.start <= l && r <= range.end {
+                self.add(k, l, r, line);
+                continue;
+            }
+            let m = l + (r - l) / 2;
+            stack.push((self.child(k, 0), l, m));
+            stack.push((self.child(k, 1), m, r));
+        }
+    }
+
+    /// `x`での最小値(または最大値)を返す. `x`を含む直線や線分が無い場合は`None`を返す
+    ///
+    /// # Constraints
+    ///
+    /// - `x`は区間に含まれる.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*) (*N*は区間の大きさ)
+    #[must_use]
+    pub fn query(&self, x: i64) -> Option<i64> {
+        debug_assert!(self.left <= x && x < self.right);
+        let (mut l, mut r) = (self.left, self.right);
+        let mut k = 0;
+        let mut res: Option<i64> = None;
+        loop {
+            if let Some(line) = self.nodes[k].0 {
+                let y = eval(line, x);
+                res = Some(res.map_or(y, |v| v.min(y)));
+            }
+            let m = l + (r - l) / 2;
+            let b = usize::from(m <= x);
+            if r - l == 1 || self.nodes[k].1[b] == 0 {
+                break;
+            }
+            if b == 0 {
+                r = m;
+            } else {
+                l = m;
+            }
+            k = self.nodes[k].1[b];
+        }
+        if self.max {
+            res.map(|v| -v)
+        } else {
+            res
+        }
+    }
+}
+
+/// 傾きが単調な直線の追加と, ある点での最小値(または最大値)の取得を処理する
+#[derive(Clone, Debug)]
+pub struct ConvexHullTrick(VecDeque<Line>, bool);
+
+impl ConvexHullTrick {
+    /// 最小値を求めるConvexHullTrickを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn new_min() -> Self {
+        Self(VecDeque::new(), false)
+    }
+
+    /// 最大値を求めるConvexHullTrickを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn new_max() -> Self {
+        Self(VecDeque::new(), true)
+    }
+
+    /// 保持している直線の数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// 直線を1つも保持していないか判定する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `l2`が`l1`と`l3`に対して不要か判定する
+    fn is_redundant(l1: Line, l2: Line, l3: Line) -> bool {
+        (l3.1 as i128 - l1.1 as i128) * (l1.0 as i128 - l2.0 as i128)
+            <= (l2.1 as i128 - l1.1 as i128) * (l1.0 as i128 - l3.0 as i128)
+    }
+
+    /// 直線`y = a x + b`を追加する
+    ///
+    /// # Constraints
+    ///
+    /// - 最小値を求める場合, `a`はそれまでに追加した直線の傾き以下である.
+    /// - 最大値を求める場合, `a`はそれまでに追加した直線の傾き以上である.
+    ///
+    /// # Time complexity
+    ///
+    /// - ならし*O*(1)
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        let line = if self.1 { (-a, -b) } else { (a, b) };
+        if let Some(&last) = self.0.back() {
+            debug_assert!(line.0 <= last.0);
+            if last.0 == line.0 {
+                if last.1 <= line.1 {
+                    return;
+                }
+                self.0.pop_back();
+            }
+        }
+        while self.0.len() >= 2
+            && Self::is_redundant(self.0[self.0.len() - 2], self.0[self.0.len() - 1], line)
+        {
+            self.0.pop_back();
+        }
+        self.0.push_back(line);
+    }
+
+    fn result(&self, v: i64) -> i64 {
+        if self.1 {
+            -v
+        } else {
+            v
+        }
+    }
+
+    /// `x`での最小値(または最大値)を返す
+    ///
+    /// # Constraints
+    ///
+    /// - `!self.is_empty()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn query(&self, x: i64) -> i64 {
+        debug_assert!(!self.is_empty());
+        let (mut ok, mut ng) = (0, self.0.len());
+        while ng - ok > 1 {
+            let m = (ok + ng) / 2;
+            if eval(self.0[m], x) <= eval(self.0[m - 1], x) {
+                ok = m;
+            } else {
+                ng = m;
+            }
+        }
+        self.result(eval(self.0[ok], x))
+    }
+
+    /// `x`での最小値(または最大値)を返す. 前回の`query_monotone`以上の`x`でのみ呼び出せる
+    ///
+    /// # Constraints
+    ///
+    /// - `!self.is_empty()`
+    /// - `x`はそれまでに`query_monotone`に与えた値以上である.
+    ///
+    /// # Time complexity
+    ///
+    /// - ならし*O*(1)
+    pub fn query_monotone(&mut self, x: i64) -> i64 {
+        debug_assert!(!self.is_empty());
+        while self.0.len() >= 2 && eval(self.0[1], x) <= eval(self.0[0], x) {
+            self.0.pop_front();
+        }
+        self.result(eval(self.0[0], x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn li_chao_tree() {
+        let mut min = LiChaoTree::new_min(-50..50);
+        let mut max = LiChaoTree::new_max(-50..50);
+        let mut lines = vec![];
+        for i in 0..20i64 {
+            let (a, b) = ((i * 7 % 11) - 5, (i * 13 % 17) - 8);
+            let range = if i % 3 == 0 {
+                -50..50
+            } else {
+                (i * 5 % 60 - 50)..(i * 5 % 60 - 50 + i * 3).min(50)
+            };
+            if i % 3 == 0 {
+                min.add_line(a, b);
+                max.add_line(a, b);
+            } else {
+                min.add_segment(range.clone(), a, b);
+                max.add_segment(range.clone(), a, b);
+            }
+            lines.push((range, a, b));
+            for x in -50..50 {
+                let ys = lines
+                    .iter()
+                    .filter(|(r, _, _)| r.contains(&x))
+                    .map(|&(_, a, b)| a * x + b);
+                assert_eq!(min.query(x), ys.clone().min());
+                assert_eq!(max.query(x), ys.max());
+            }
+        }
+    }
+
+    #[test]
+    fn convex_hull_trick() {
+        let mut min = ConvexHullTrick::new_min();
+        let mut max = ConvexHullTrick::new_max();
+        let mut lines = vec![];
+        for i in 0..30i64 {
+            let (a, b) = (10 - i / 2, (i * 37 % 23) - 11);
+            min.add_line(a, b);
+            max.add_line(-a, -b);
+            lines.push((a, b));
+            for x in -20..20 {
+                let y = lines.iter().map(|&(a, b)| a * x + b).min().unwrap();
+                assert_eq!(min.query(x), y);
+                assert_eq!(max.query(x), -y);
+            }
+        }
+        for x in -20..20 {
+            let y = lines.iter().map(|&(a, b)| a * x + b).min().unwrap();
+            assert_eq!(min.query_monotone(x), y);
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use super::util::to_range;
+
+const BLOCK: usize = u64::BITS as usize;
+
+/// 区間最小値とその位置を定数時間で求めるデータ構造
+///
+/// SparseTableと異なり, 使用するメモリは*O*(*N*)である.
+/// 最大値を求める場合は`std::cmp::Reverse`で包むとよい.
+#[derive(Clone)]
+pub struct LinearRmq<T: Ord> {
+    items: Box<[T]>,
+    /// `masks[i]`は`i`を含むブロックの先頭から`i`までを見たときの単調スタックに含まれる位置の集合
+    masks: Box<[u64]>,
+    /// ブロックごとの最小値の位置のSparseTable
+    table: Box<[usize]>,
+}
+
+impl<T: Ord> LinearRmq<T> {
+    /// 列からLinearRmqを構築する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*N*)
+    #[must_use]
+    pub fn new(items: impl Into<Vec<T>>) -> Self {
+        let items: Box<[T]> = items.into().into_boxed_slice();
+        let len = items.len();
+        let mut masks = Vec::with_capacity(len);
+        for block in items.chunks(BLOCK) {
+            let mut mask = 0u64;
+            for (i, v) in block.iter().enumerate() {
+                while mask != 0 {
+                    let top = (u64::BITS - 1 - mask.leading_zeros()) as usize;
+                    if block[top] <= *v {
+                        break;
+                    }
+                    mask ^= 1 << top;
+                }
+                mask |= 1 << i;
+                masks.push(mask);
+            }
+        }
+        let blocks = len.div_ceil(BLOCK);
+        let mut table: Vec<usize> = (0..blocks)
+            .map(|b| {
+                b * BLOCK + masks[(b * BLOCK + BLOCK - 1).min(len - 1)].trailing_zeros() as usize
+            })
+            .collect();
+        let mut span = 1;
+        let mut offset = 0;
+        while span * 2 <= blocks {
+            for i in 0..=blocks - span * 2 {
+                let (a, b) = (table[offset + i], table[offset + i + span]);
+                table.push(if items[b] < items[a] { b } else { a });
+            }
+            offset += blocks - span + 1;
+            span *= 2;
+        }
+        Self {
+            items,
+            masks: masks.into(),
+            table: table.into(),
+        }
+    }
+
+    /// 列の長さを返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// 列が空かどうか調べる
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn min_index(&self, a: usize, b: usize) -> usize {
+        if self.items[b] < self.items[a] {
+            b
+        } else {
+            a
+        }
+    }
+
+    /// 同じブロック内の`left..=right`の最小値の位置
+    fn argmin_in_block(&self, left: usize, right: usize) -> usize {
+        let start = left / BLOCK * BLOCK;
+        start + (self.masks[right] >> (left - start)).trailing_zeros() as usize + (left - start)
+    }
+
+    /// ブロック`left..right`の最小値の位置
+    fn argmin_blocks(&self, left: usize, right: usize) -> usize {
+        let blocks = self.len().div_ceil(BLOCK);
+        let log = (usize::BITS - (right - left).leading_zeros() - 1) as usize;
+        let offset = (blocks + 1) * log - ((1 << log) - 1);
+        self.min_index(
+            self.table[offset + left],
+            self.table[offset + right - (1 << log)],
+        )
+    }
+
+    /// `range`の範囲の最小値の位置を返す. 最小値が複数ある場合は最も左の位置を返す
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる空でない区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn argmin(&self, range: impl std::ops::RangeBounds<usize>) -> usize {
+        let (left, right) = to_range(range, self.len());
+        debug_assert!(left < right);
+        let last = right - 1;
+        let (lb, rb) = (left / BLOCK, last / BLOCK);
+        if lb == rb {
+            return self.argmin_in_block(left, last);
+        }
+        let mut index = self.argmin_in_block(left, lb * BLOCK + BLOCK - 1);
+        if lb + 1 < rb {
+            index = self.min_index(index, self.argmin_blocks(lb + 1, rb));
+        }
+        self.min_index(index, self.argmin_in_block(rb * BLOCK, last))
+    }
+
+    /// `range`の範囲の最小値を返す
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる空でない区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn min(&self, range: impl std::ops::RangeBounds<usize>) -> &T {
+        &self.items[self.argmin(range)]
+    }
+}
+
+impl<T: Ord> std::ops::Index<usize> for LinearRmq<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.items[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brute() {
+        let items = (0..300u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 59)
+            .collect::<Vec<_>>();
+        let rmq = LinearRmq::new(items.clone());
+        for l in 0..items.len() {
+            for r in l + 1..=items.len() {
+                let expected = (l..r).min_by_key(|&i| items[i]).unwrap();
+                assert_eq!(rmq.argmin(l..r), expected);
+                assert_eq!(*rmq.min(l..r), items[expected]);
+            }
+        }
+    }
+}
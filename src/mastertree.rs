@@ -1,3 +1,4 @@
+use super::util::to_range;
 use std::{cell::UnsafeCell, rc::Rc};
 
 /// 疑似乱数生成器 (xorshift64)
@@ -25,6 +26,29 @@ impl Rng {
         };
         Self(SEED)
     }
+    /// `seed`から状態を作る. 状態が0にならないようにsplitmix64で撹拌する
+    fn from_seed(seed: u64) -> Self {
+        let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        Self(if z == 0 { 0x9e3779b97f4a7c15 } else { z })
+    }
+    /// 実行時の情報(時刻・アドレス・プロセスごとのハッシュの鍵)からシードを作る
+    fn from_entropy() -> Self {
+        use std::hash::{BuildHasher, Hasher};
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        if let Ok(d) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            hasher.write_u128(d.as_nanos());
+        }
+        let local = 0u8;
+        hasher.write_usize(&local as *const u8 as usize);
+        Self::from_seed(hasher.finish())
+    }
+    /// 2つの状態を混ぜた状態を作る
+    fn mix(self, other: Self) -> Self {
+        Self::from_seed(self.0 ^ other.0.rotate_left(32))
+    }
     fn gen(&mut self) -> u64 {
         self.0 ^= self.0 << 7;
         self.0 ^= self.0 >> 9;
@@ -66,6 +90,10 @@ pub trait MasterManager {
     ) -> Self::Info;
 
     /// 列が反転されたときの区間の情報を得る
+    ///
+    /// 長さ`len`の部分木が反転されることが決まった時点で, その根の`info`に対して1度だけ呼ばれる.
+    /// `info`は反転前の区間の情報で, 呼び出し後は反転後の区間の情報になっている必要がある.
+    /// 子の部分木の反転は遅延され, その子を辿るときに子の`info`に対して改めて呼ばれる.
     fn rev(info: &mut Self::Info, len: usize);
     /// Infoに作用素を適用する
     fn apply_info(info: &mut Self::Info, len: usize, lazy: &Self::Lazy);
@@ -94,10 +122,18 @@ pub trait MasterManager {
     fn op(left: Self::Prod, right: Self::Prod) -> Self::Prod;
 }
 
+/// 列を`cmp`の順に整列した状態で扱うためのトレイト
+pub trait OrderedMasterManager: MasterManager {
+    /// 列の値の比較関数
+    #[must_use]
+    fn cmp(lhs: &Self::T, rhs: &Self::T) -> std::cmp::Ordering;
+}
+
 struct Node<M: MasterManager> {
     val: M::T,
     info: M::Info,
     idx: usize,
+    /// 子の反転を遅延しているか (`info`は反転済み)
     rev: bool,
     left: Option<NodeWrapper<M>>,
     right: Option<NodeWrapper<M>>,
@@ -126,15 +162,14 @@ impl<M: MasterManager> Node<M> {
             ),
         );
         if self.rev {
-            M::rev(&mut self.info, len);
             std::mem::swap(&mut self.left, &mut self.right);
+            self.idx = len - self.idx - 1;
             if let Some(v) = &mut self.left {
-                v.as_mut().rev ^= true;
+                v.reverse(self.idx);
             }
             if let Some(v) = &mut self.right {
-                v.as_mut().rev ^= true;
+                v.reverse(len - 1 - self.idx);
             }
-            self.idx = len - self.idx - 1;
             self.rev = false;
         }
     }
@@ -189,12 +224,121 @@ impl<M: MasterManager> NodeWrapper<M> {
         })))
     }
 
+    /// 長さ`len`の木を反転する. 子の反転は遅延させる
+    fn reverse(&mut self, len: usize) {
+        let node = self.as_mut();
+        M::rev(&mut node.info, len);
+        node.rev ^= true;
+    }
+
     fn setup(&self, len: usize) -> &Node<M> {
         let r = unsafe { &mut *self.0.get() };
         r.setup(len);
         r
     }
 
+    /// `iter`の先頭`len`個の値から完全にバランスした木を作る
+    fn build(iter: &mut impl Iterator<Item = M::T>, len: usize) -> Option<Self> {
+        if len == 0 {
+            return None;
+        }
+        let idx = len / 2;
+        let left = Self::build(iter, idx);
+        let val = iter.next().unwrap();
+        let right = Self::build(iter, len - 1 - idx);
+        let info = M::make_info(
+            (left.as_ref().map(|v| &v.as_ref().info), idx),
+            &val,
+            (right.as_ref().map(|v| &v.as_ref().info), len - 1 - idx),
+        );
+        Some(Self(Rc::new(UnsafeCell::new(Node {
+            val,
+            info,
+            idx,
+            rev: false,
+            left,
+            right,
+        }))))
+    }
+
+    /// 長さ`len`の木の値を順に`out`に追加する
+    fn collect(this: &Option<Self>, len: usize, out: &mut Vec<M::T>) {
+        let Some(this) = this else {
+            return;
+        };
+        let node = this.setup(len);
+        Self::collect(&node.left, node.idx, out);
+        out.push(node.val.clone());
+        Self::collect(&node.right, len - 1 - node.idx, out);
+    }
+
+    /// 長さ`len`の木を分解して, 値を順に`out`に追加する
+    fn into_vec(this: Option<Self>, len: usize, out: &mut Vec<M::T>) {
+        let Some(this) = this else {
+            return;
+        };
+        this.setup(len);
+        match Rc::try_unwrap(this.0) {
+            Ok(node) => {
+                let node = node.into_inner();
+                Self::into_vec(node.left, node.idx, out);
+                out.push(node.val);
+                Self::into_vec(node.right, len - 1 - node.idx, out);
+            }
+            Err(rc) => Self::collect(&Some(Self(rc)), len, out),
+        }
+    }
+
+    fn set(&mut self, len: usize, index: usize, item: M::T) {
+        use std::cmp::Ordering::*;
+        self.setup(len);
+        let node = self.as_mut();
+        let idx = node.idx;
+        match index.cmp(&idx) {
+            Less => node.left.as_mut().unwrap().set(idx, index, item),
+            Equal => node.val = item,
+            Greater => node
+                .right
+                .as_mut()
+                .unwrap()
+                .set(len - 1 - idx, index - 1 - idx, item),
+        }
+        node.update(len);
+    }
+
+    /// 長さ`len`の木の区間`left..right`の値を`iter`の値で順に置き換える
+    ///
+    /// `left < right`である必要がある
+    fn assign(
+        &mut self,
+        len: usize,
+        left: usize,
+        right: usize,
+        iter: &mut impl Iterator<Item = M::T>,
+    ) {
+        self.setup(len);
+        let node = self.as_mut();
+        let idx = node.idx;
+        if left < idx {
+            node.left
+                .as_mut()
+                .unwrap()
+                .assign(idx, left, right.min(idx), iter);
+        }
+        if left <= idx && idx < right {
+            node.val = iter.next().unwrap();
+        }
+        if idx + 1 < right {
+            node.right.as_mut().unwrap().assign(
+                len - 1 - idx,
+                left.saturating_sub(idx + 1),
+                right - 1 - idx,
+                iter,
+            );
+        }
+        node.update(len);
+    }
+
     fn index(&self, mut len: usize, mut index: usize) -> &M::T {
         use std::cmp::Ordering::*;
         let mut node = self.setup(len);
@@ -318,6 +462,7 @@ impl<M: MasterManager> NodeWrapper<M> {
             Less => {
                 let (v, o) = node.left.take().unwrap().remove(idx, index, rng);
                 node.left = o;
+                node.idx -= 1;
                 node.update(len - 1);
                 (v, Some(self))
             }
@@ -339,7 +484,7 @@ impl<M: MasterManager> NodeWrapper<M> {
                     .take()
                     .unwrap()
                     .remove(len - 1 - idx, index - 1 - idx, rng);
-                node.left = o;
+                node.right = o;
                 node.update(len - 1);
                 (v, Some(self))
             }
@@ -510,6 +655,77 @@ impl<M: MasterManager> NodeWrapper<M> {
         }
         node.update(len);
     }
+    /// `left`から右へ`acc`に積を取っていき, `f`が`false`になる位置を返す
+    fn max_right(
+        &self,
+        len: usize,
+        left: usize,
+        acc: &mut M::Prod,
+        f: &impl Fn(&M::Prod) -> bool,
+    ) -> Option<usize> {
+        let node = self.setup(len);
+        if left == 0 {
+            let x = M::op(acc.clone(), M::info2prod(&node.info));
+            if f(&x) {
+                *acc = x;
+                return None;
+            }
+        }
+        let idx = node.idx;
+        if left < idx {
+            if let Some(p) = node.left.as_ref().unwrap().max_right(idx, left, acc, f) {
+                return Some(p);
+            }
+        }
+        if left <= idx {
+            let x = M::op(acc.clone(), M::val2prod(&node.val));
+            if !f(&x) {
+                return Some(idx);
+            }
+            *acc = x;
+        }
+        node.right
+            .as_ref()?
+            .max_right(len - 1 - idx, left.saturating_sub(idx + 1), acc, f)
+            .map(|p| p + idx + 1)
+    }
+
+    /// `right`から左へ`acc`に積を取っていき, `f`が`false`になる位置の右端を返す
+    fn min_left(
+        &self,
+        len: usize,
+        right: usize,
+        acc: &mut M::Prod,
+        f: &impl Fn(&M::Prod) -> bool,
+    ) -> Option<usize> {
+        let node = self.setup(len);
+        if right == len {
+            let x = M::op(M::info2prod(&node.info), acc.clone());
+            if f(&x) {
+                *acc = x;
+                return None;
+            }
+        }
+        let idx = node.idx;
+        if right > idx + 1 {
+            if let Some(p) =
+                node.right
+                    .as_ref()
+                    .unwrap()
+                    .min_left(len - 1 - idx, right - 1 - idx, acc, f)
+            {
+                return Some(p + idx + 1);
+            }
+        }
+        if right > idx {
+            let x = M::op(M::val2prod(&node.val), acc.clone());
+            if !f(&x) {
+                return Some(idx + 1);
+            }
+            *acc = x;
+        }
+        node.left.as_ref()?.min_left(idx, right.min(idx), acc, f)
+    }
 }
 
 /// MasterTree. 大量の機能が詰め込まれた平衡二分木
@@ -525,6 +741,29 @@ impl<M: MasterManager> MasterTree<M> {
         Self(None, 0, Rng::new())
     }
 
+    /// 平衡に使う乱数のシードを指定して, 空の列のMasterTreeを作る
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn with_seed(seed: u64) -> Self {
+        Self(None, 0, Rng::from_seed(seed))
+    }
+
+    /// 平衡に使う乱数のシードを実行時の情報から決めて, 空の列のMasterTreeを作る
+    ///
+    /// `new`はコンパイル時に決まるシードを使うため, 入力によっては平衡が崩れうる.
+    /// そのような入力を避けたい場合に使う.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn with_random_seed() -> Self {
+        Self(None, 0, Rng::from_entropy())
+    }
+
     /// 列の長さを返す
     ///
     /// # Time complexity
@@ -547,6 +786,8 @@ impl<M: MasterManager> MasterTree<M> {
 
     /// 2つの列を混ぜた新しいMasterTreeを作る
     ///
+    /// 新しいMasterTreeの乱数の状態は, 両方の乱数の状態から決まる
+    ///
     /// # Constraints
     ///
     /// - 元の列の長さの和が`usize::MAX`以下
@@ -556,7 +797,7 @@ impl<M: MasterManager> MasterTree<M> {
     /// - *O*(log *N*)
     #[must_use]
     pub fn merge(self, rhs: Self) -> Self {
-        let mut rng = self.2;
+        let mut rng = self.2.mix(rhs.2);
         let new_len = self
             .1
             .checked_add(rhs.1)
@@ -623,6 +864,188 @@ impl<M: MasterManager> MasterTree<M> {
         r
     }
 
+    /// 配列からMasterTreeを作る
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*N*)
+    #[must_use]
+    pub fn from_vec(vec: Vec<M::T>) -> Self {
+        let len = vec.len();
+        Self(
+            NodeWrapper::build(&mut vec.into_iter(), len),
+            len,
+            Rng::new(),
+        )
+    }
+
+    /// 列を配列に変換する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*N*)
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<M::T> {
+        let mut vec = Vec::with_capacity(self.1);
+        NodeWrapper::collect(&self.0, self.1, &mut vec);
+        vec
+    }
+
+    /// 指定した区間の列を, 元の列とノードを共有したMasterTreeとして取り出す
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn slice(&self, range: impl std::ops::RangeBounds<usize>) -> Self {
+        let (left, right) = to_range(range, self.1);
+        let (_, r) = self.clone().split(left);
+        r.split(right - left).0
+    }
+
+    /// 指定した区間の列の複製を, 位置`index`に挿入する
+    ///
+    /// `index`は挿入前の列での位置である. 複製は元の列とノードを共有するため,
+    /// 列の長さが指数的に増えるような操作も扱える.
+    ///
+    /// # Constraints
+    ///
+    /// - `src`は`0..self.len()`に含まれる区間である.
+    /// - `index <= self.len()`
+    /// - 挿入後の列の長さが`usize::MAX`以下
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn copy_range(&mut self, src: impl std::ops::RangeBounds<usize>, index: usize) {
+        debug_assert!(index <= self.len());
+        let copy = self.slice(src);
+        let (l, r) = std::mem::take(self).split(index);
+        *self = l.merge(copy).merge(r);
+    }
+
+    /// 現在の列だけからなる, 他の版とノードを共有しない完全にバランスした木に作り直す
+    ///
+    /// `copy_range`などで共有されたノードや, 他の版のために複製されたノードへの参照を手放すため,
+    /// 長く使われる永続的な処理で使用メモリを抑えるのに使う.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*N*)
+    pub fn rebuild(&mut self) {
+        let vec = self.to_vec();
+        self.0 = NodeWrapper::build(&mut vec.into_iter(), self.1);
+    }
+
+    /// 列から辿れる相異なるノードの数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*K*) (*K*は返り値)
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        let mut visited = std::collections::HashSet::new();
+        let mut stack: Vec<&NodeWrapper<M>> = self.0.iter().collect();
+        while let Some(node) = stack.pop() {
+            if visited.insert(Rc::as_ptr(&node.0)) {
+                let node = node.as_ref();
+                stack.extend(node.left.iter());
+                stack.extend(node.right.iter());
+            }
+        }
+        visited.len()
+    }
+
+    /// 列を`left..right`の前, 中, 後ろの3つに分ける
+    fn split3(&mut self, left: usize, right: usize) -> (Self, Self, Self) {
+        let (l, r) = std::mem::take(self).split(left);
+        let (m, r) = r.split(right - left);
+        (l, m, r)
+    }
+
+    /// 指定した区間を前後反転する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn reverse_range(&mut self, range: impl std::ops::RangeBounds<usize>) {
+        let (left, right) = to_range(range, self.1);
+        let (l, mut m, r) = self.split3(left, right);
+        m.reverse();
+        *self = l.merge(m).merge(r);
+    }
+
+    /// 指定した区間を, 先頭の`k`個が末尾に来るように回転する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    /// - `k`は区間の長さ以下である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn rotate_left(&mut self, range: impl std::ops::RangeBounds<usize>, k: usize) {
+        let (left, right) = to_range(range, self.1);
+        debug_assert!(k <= right - left);
+        let (l, m, r) = self.split3(left, right);
+        let (a, b) = m.split(k);
+        *self = l.merge(b).merge(a).merge(r);
+    }
+
+    /// 指定した区間を取り除き, 残った列の位置`index`に挿入する
+    ///
+    /// # Constraints
+    ///
+    /// - `src`は`0..self.len()`に含まれる区間である.
+    /// - `index`は`src`を取り除いた後の列の長さ以下である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn move_range(&mut self, src: impl std::ops::RangeBounds<usize>, index: usize) {
+        let (left, right) = to_range(src, self.1);
+        debug_assert!(index <= self.len() - (right - left));
+        let (l, m, r) = self.split3(left, right);
+        let (l, r) = l.merge(r).split(index);
+        *self = l.merge(m).merge(r);
+    }
+
+    /// 重ならない2つの区間を入れ替える. 区間の長さは異なってもよい
+    ///
+    /// # Constraints
+    ///
+    /// - `a`, `b`は`0..self.len()`に含まれる区間である.
+    /// - `a`と`b`は重ならない.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn swap_ranges(
+        &mut self,
+        a: impl std::ops::RangeBounds<usize>,
+        b: impl std::ops::RangeBounds<usize>,
+    ) {
+        let (a, b) = (to_range(a, self.1), to_range(b, self.1));
+        let ((l1, r1), (l2, r2)) = if a.0 <= b.0 { (a, b) } else { (b, a) };
+        debug_assert!(r1 <= l2);
+        let (l, x, r) = self.split3(l1, r2);
+        let (x, y, z) = {
+            let (x, rest) = x.split(r1 - l1);
+            let (y, z) = rest.split(l2 - r1);
+            (x, y, z)
+        };
+        *self = l.merge(z).merge(y).merge(x).merge(r);
+    }
+
     /// 列を前後反転する
     ///
     /// # Time complexity
@@ -630,55 +1053,225 @@ impl<M: MasterManager> MasterTree<M> {
     /// - *O*(1)
     pub fn reverse(&mut self) {
         if let Some(node) = &mut self.0 {
-            node.as_mut().rev ^= true;
+            node.reverse(self.1);
         }
     }
 
     /// イテレータを返す
-    pub fn iter(&self) -> impl ExactSizeIterator<Item = &M::T> + use<'_, M> {
-        struct Iter<'a, M: MasterManager>(Vec<(&'a UnsafeCell<Node<M>>, usize)>, usize);
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &M::T> + ExactSizeIterator + use<'_, M> {
+        self.range(..)
+    }
+
+    /// 指定した区間の値を順に返すイテレータを返す
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*) (構築)
+    /// - ならし*O*(1) (各要素)
+    pub fn range<R: std::ops::RangeBounds<usize>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = &M::T> + ExactSizeIterator + use<'_, M, R> {
+        /// 前から見るためのスタックと後ろから見るためのスタック, 残りの要素数
+        struct Iter<'a, M: MasterManager> {
+            front: Vec<(&'a Node<M>, usize)>,
+            back: Vec<(&'a Node<M>, usize)>,
+            rest: usize,
+        }
         impl<'a, M: MasterManager> Iterator for Iter<'a, M> {
             type Item = &'a M::T;
             fn next(&mut self) -> Option<&'a M::T> {
-                let (node, mut len) = self.0.pop()?;
-                let f = unsafe { &*node.get() };
-                let ret = &f.val;
-                let mut node = &f.right;
-                len -= f.idx + 1;
-                while let Some(ni) = node {
-                    let f = ni.0.as_ref();
-                    unsafe { &mut *f.get() }.setup(self.len());
-                    self.0.push((f, len));
-                    let f = unsafe { &*f.get() };
-                    node = &f.left;
+                if self.rest == 0 {
+                    return None;
+                }
+                self.rest -= 1;
+                let (node, len) = self.front.pop()?;
+                let mut next = &node.right;
+                let mut len = len - 1 - node.idx;
+                while let Some(ni) = next {
+                    let f = ni.setup(len);
+                    self.front.push((f, len));
+                    next = &f.left;
                     len = f.idx;
                 }
-                Some(ret)
+                Some(&node.val)
             }
 
             fn size_hint(&self) -> (usize, Option<usize>) {
-                (self.1, Some(self.1))
+                (self.rest, Some(self.rest))
+            }
+        }
+        impl<M: MasterManager> DoubleEndedIterator for Iter<'_, M> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                if self.rest == 0 {
+                    return None;
+                }
+                self.rest -= 1;
+                let (node, _) = self.back.pop()?;
+                let mut next = &node.left;
+                let mut len = node.idx;
+                while let Some(ni) = next {
+                    let f = ni.setup(len);
+                    self.back.push((f, len));
+                    next = &f.right;
+                    len -= f.idx + 1;
+                }
+                Some(&node.val)
             }
         }
         impl<M: MasterManager> ExactSizeIterator for Iter<'_, M> {
             fn len(&self) -> usize {
-                self.1
+                self.rest
             }
         }
 
-        let mut node = &self.0;
-        let mut len = self.len();
-        let mut vec = vec![];
-        while let Some(ni) = node {
-            let f = ni.0.as_ref();
-            unsafe { &mut *f.get() }.setup(self.len());
-            vec.push((f, len));
-            let f = unsafe { &*f.get() };
-            node = &f.left;
-            len = f.idx;
+        let (left, right) = to_range(range, self.1);
+        let mut front = vec![];
+        let mut back = vec![];
+        if left < right {
+            let (mut node, mut len, mut index) = (&self.0, self.1, left);
+            while let Some(ni) = node {
+                let f = ni.setup(len);
+                if index <= f.idx {
+                    front.push((f, len));
+                    if index == f.idx {
+                        break;
+                    }
+                    node = &f.left;
+                    len = f.idx;
+                } else {
+                    index -= f.idx + 1;
+                    len -= f.idx + 1;
+                    node = &f.right;
+                }
+            }
+            let (mut node, mut len, mut index) = (&self.0, self.1, right - 1);
+            while let Some(ni) = node {
+                let f = ni.setup(len);
+                if index >= f.idx {
+                    back.push((f, len));
+                    if index == f.idx {
+                        break;
+                    }
+                    index -= f.idx + 1;
+                    len -= f.idx + 1;
+                    node = &f.right;
+                } else {
+                    len = f.idx;
+                    node = &f.left;
+                }
+            }
+        }
+        Iter {
+            front,
+            back,
+            rest: right - left,
+        }
+    }
+
+    /// 指定した場所の値を設定する
+    ///
+    /// # Constraints
+    ///
+    /// - `index < self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn set(&mut self, index: usize, item: M::T) {
+        debug_assert!(index < self.len());
+        self.0.as_mut().unwrap().set(self.1, index, item);
+    }
+
+    /// 指定した場所の値の可変参照(のラッパー)を取得する.
+    ///
+    /// # Constraints
+    ///
+    /// - `index < self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    /// - *O*(log *N*)  (ラッパーのデストラクタ)
+    #[must_use]
+    pub fn setter(&mut self, index: usize) -> impl std::ops::DerefMut<Target = M::T> + use<'_, M> {
+        debug_assert!(index < self.len());
+
+        struct Wrapper<'a, M: MasterManager>(&'a mut MasterTree<M>, usize, Option<M::T>);
+        impl<M: MasterManager> std::ops::Deref for Wrapper<'_, M> {
+            type Target = M::T;
+            fn deref(&self) -> &M::T {
+                self.2.as_ref().unwrap()
+            }
+        }
+        impl<M: MasterManager> std::ops::DerefMut for Wrapper<'_, M> {
+            fn deref_mut(&mut self) -> &mut M::T {
+                self.2.as_mut().unwrap()
+            }
+        }
+        impl<M: MasterManager> Drop for Wrapper<'_, M> {
+            fn drop(&mut self) {
+                self.0.set(self.1, self.2.take().unwrap());
+            }
         }
 
-        Iter(vec, self.len())
+        let item = self[index].clone();
+        Wrapper(self, index, Some(item))
+    }
+
+    /// 指定した区間の値のスライスの可変参照(のラッパー)を取得する.
+    ///
+    /// 変更はラッパーのデストラクタで木に書き戻される.
+    /// ラッパーが破棄されなかった場合, 列は変更前のまま残る.
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*K* + log *N*) (*K*は区間の長さ)
+    /// - *O*(*K* + log *N*)  (ラッパーのデストラクタ)
+    #[must_use]
+    pub fn range_mut<R: std::ops::RangeBounds<usize>>(
+        &mut self,
+        range: R,
+    ) -> impl std::ops::DerefMut<Target = [M::T]> + use<'_, M, R> {
+        struct Wrapper<'a, M: MasterManager>(&'a mut MasterTree<M>, usize, Vec<M::T>);
+        impl<M: MasterManager> std::ops::Deref for Wrapper<'_, M> {
+            type Target = [M::T];
+            fn deref(&self) -> &[M::T] {
+                &self.2
+            }
+        }
+        impl<M: MasterManager> std::ops::DerefMut for Wrapper<'_, M> {
+            fn deref_mut(&mut self) -> &mut [M::T] {
+                &mut self.2
+            }
+        }
+        impl<M: MasterManager> Drop for Wrapper<'_, M> {
+            fn drop(&mut self) {
+                if self.2.is_empty() {
+                    return;
+                }
+                let (left, right) = (self.1, self.1 + self.2.len());
+                let len = self.0 .1;
+                self.0 .0.as_mut().unwrap().assign(
+                    len,
+                    left,
+                    right,
+                    &mut std::mem::take(&mut self.2).into_iter(),
+                );
+            }
+        }
+
+        let (left, right) = to_range(range, self.1);
+        let items = self.range(left..right).cloned().collect();
+        Wrapper(self, left, items)
     }
 
     /// 指定した区間のモノイド総積を求める
@@ -798,6 +1391,171 @@ impl<M: MasterManager> MasterTree<M> {
         }
         self.0.as_mut().unwrap().apply(len, left, right, lazy);
     }
+    /// `f(self.prod(left..x))`が`true`となる最大の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(self.prod(left..x))`かつ (`x == self.len()`または`!f(self.prod(left..=x))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `left <= self.len()`
+    /// - `f(M::e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn max_right(&self, left: usize, f: impl Fn(&M::Prod) -> bool) -> usize {
+        debug_assert!(left <= self.len());
+        let Some(node) = self.0.as_ref().filter(|_| left < self.1) else {
+            return self.1;
+        };
+        let mut acc = M::e();
+        node.max_right(self.1, left, &mut acc, &f).unwrap_or(self.1)
+    }
+
+    /// `f(self.prod(x..right))`が`true`となる最小の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(self.prod(x..right))`かつ (`x == 0`または`!f(self.prod(x - 1..right))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `right <= self.len()`
+    /// - `f(M::e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn min_left(&self, right: usize, f: impl Fn(&M::Prod) -> bool) -> usize {
+        debug_assert!(right <= self.len());
+        let Some(node) = self.0.as_ref().filter(|_| right > 0) else {
+            return 0;
+        };
+        let mut acc = M::e();
+        node.min_left(self.1, right, &mut acc, &f).unwrap_or(0)
+    }
+
+    /// `f(&self[i])`が`false`となる最小の`i`を返す. 存在しない場合は`self.len()`を返す
+    ///
+    /// # Constraints
+    ///
+    /// - `f`の値は列の先頭から順に`true`が続いた後`false`が続く.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn partition_point(&self, mut f: impl FnMut(&M::T) -> bool) -> usize {
+        let mut node = &self.0;
+        let mut len = self.1;
+        let mut ret = 0;
+        while let Some(ni) = node {
+            let n = ni.setup(len);
+            if f(&n.val) {
+                ret += n.idx + 1;
+                len -= n.idx + 1;
+                node = &n.right;
+            } else {
+                len = n.idx;
+                node = &n.left;
+            }
+        }
+        ret
+    }
+}
+
+impl<M: OrderedMasterManager> MasterTree<M> {
+    /// `key`以上の値が初めて現れる位置を返す
+    ///
+    /// # Constraints
+    ///
+    /// - 列は`M::cmp`の順に整列されている.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn lower_bound(&self, key: &M::T) -> usize {
+        self.partition_point(|v| M::cmp(v, key).is_lt())
+    }
+
+    /// `key`より大きい値が初めて現れる位置を返す
+    ///
+    /// # Constraints
+    ///
+    /// - 列は`M::cmp`の順に整列されている.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn upper_bound(&self, key: &M::T) -> usize {
+        self.partition_point(|v| M::cmp(v, key).is_le())
+    }
+
+    /// `key`未満の値の個数を返す
+    ///
+    /// # Constraints
+    ///
+    /// - 列は`M::cmp`の順に整列されている.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn rank(&self, key: &M::T) -> usize {
+        self.lower_bound(key)
+    }
+
+    /// 整列された状態を保つ位置に値を挿入し, その位置を返す
+    ///
+    /// 等しい値が既にある場合は, それらの後ろに挿入する
+    ///
+    /// # Constraints
+    ///
+    /// - 列は`M::cmp`の順に整列されている.
+    /// - `self.len() < usize::MAX`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn insert_sorted(&mut self, item: M::T) -> usize {
+        let index = self.upper_bound(&item);
+        self.insert(index, item);
+        index
+    }
+
+    /// `key`と等しい値を1つ削除し, その値を返す. 存在しない場合は`None`を返す
+    ///
+    /// # Constraints
+    ///
+    /// - 列は`M::cmp`の順に整列されている.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn remove_sorted(&mut self, key: &M::T) -> Option<M::T> {
+        let index = self.lower_bound(key);
+        if index == self.len() || M::cmp(&self[index], key).is_ne() {
+            return None;
+        }
+        Some(self.remove(index))
+    }
+
+    /// `key`未満の値からなる列と`key`以上の値からなる列に分ける
+    ///
+    /// # Constraints
+    ///
+    /// - 列は`M::cmp`の順に整列されている.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn split_by_key(self, key: &M::T) -> (Self, Self) {
+        let index = self.lower_bound(key);
+        self.split(index)
+    }
 }
 
 impl<M: MasterManager> Clone for MasterTree<M> {
@@ -841,6 +1599,39 @@ impl<M: MasterManager<T: std::fmt::Debug>> std::fmt::Debug for MasterTree<M> {
     }
 }
 
+impl<M: MasterManager> FromIterator<M::T> for MasterTree<M> {
+    fn from_iter<I: IntoIterator<Item = M::T>>(iter: I) -> Self {
+        Self::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<M: MasterManager> Extend<M::T> for MasterTree<M> {
+    fn extend<I: IntoIterator<Item = M::T>>(&mut self, iter: I) {
+        let vec: Vec<M::T> = iter.into_iter().collect();
+        let len = vec.len();
+        let Some(rhs) = NodeWrapper::build(&mut vec.into_iter(), len) else {
+            return;
+        };
+        let new_len = self.1.checked_add(len).expect("MasterTree length overflow");
+        self.0 = Some(match self.0.take() {
+            Some(lhs) => NodeWrapper::merge((lhs, self.1), (rhs, len), &mut self.2),
+            None => rhs,
+        });
+        self.1 = new_len;
+    }
+}
+
+impl<M: MasterManager> IntoIterator for MasterTree<M> {
+    type Item = M::T;
+    type IntoIter = std::vec::IntoIter<M::T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut vec = Vec::with_capacity(self.1);
+        NodeWrapper::into_vec(self.0, self.1, &mut vec);
+        vec.into_iter()
+    }
+}
+
 impl<M: MasterManager> Default for MasterTree<M> {
     fn default() -> Self {
         Self::new()
@@ -959,4 +1750,390 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn remove() {
+        use crate::monoids::RangeAddRangeSum;
+
+        let mut rng = Rng::new();
+        for _ in 0..100 {
+            let mut mt = MasterTree::<RangeAddRangeSum<i64>>::new();
+            mt.2 = rng.make();
+            let mut v = vec![];
+            for i in 0..40 {
+                let index = i * 17 % (v.len() + 1);
+                mt.insert(index, i as i64);
+                v.insert(index, i as i64);
+            }
+            for i in 0..30 {
+                let index = i * 13 % v.len();
+                assert_eq!(mt.remove(index), v.remove(index));
+                assert_eq!(mt.len(), v.len());
+                for (j, x) in v.iter().enumerate() {
+                    assert_eq!(mt[j], *x);
+                }
+                assert_eq!(mt.prod(..), v.iter().sum::<i64>());
+            }
+        }
+    }
+
+    #[test]
+    fn search() {
+        use crate::monoids::RangeAddRangeSum;
+
+        let mut rng = Rng::new();
+        for _ in 0..100 {
+            let mut mt = MasterTree::<RangeAddRangeSum<i64>>::new();
+            mt.2 = rng.make();
+            let mut v = vec![];
+            for i in 0..20 {
+                let x = i * 7 % 5;
+                mt.insert(i as usize / 2, x);
+                v.insert(i as usize / 2, x);
+            }
+            mt.reverse();
+            v.reverse();
+            mt.apply(3..9, &2);
+            for x in &mut v[3..9] {
+                *x += 2;
+            }
+            for l in 0..=v.len() {
+                for w in 0..30 {
+                    let r = (l..=v.len())
+                        .take_while(|&r| v[l..r].iter().sum::<i64>() <= w)
+                        .last()
+                        .unwrap();
+                    assert_eq!(mt.max_right(l, |&x| x <= w), r);
+                    let x = (0..=l)
+                        .rev()
+                        .take_while(|&x| v[x..l].iter().sum::<i64>() <= w)
+                        .last()
+                        .unwrap();
+                    assert_eq!(mt.min_left(l, |&x| x <= w), x);
+                }
+            }
+            let mut sorted = v.clone();
+            sorted.sort();
+            let mt = sorted.iter().enumerate().fold(
+                MasterTree::<RangeAddRangeSum<i64>>::new(),
+                |mut mt, (i, &x)| {
+                    mt.insert(i, x);
+                    mt
+                },
+            );
+            for x in 0..10 {
+                assert_eq!(
+                    mt.partition_point(|&y| y < x),
+                    sorted.partition_point(|&y| y < x)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ordered() {
+        use crate::monoids::Ordered;
+
+        let mut rng = Rng::new();
+        for _ in 0..100 {
+            let mut mt = MasterTree::<Ordered<i64>>::new();
+            mt.2 = rng.make();
+            let mut v = vec![];
+            for i in 0..30 {
+                let x = i * 7 % 11;
+                let index = mt.insert_sorted(x);
+                assert_eq!(index, v.partition_point(|&y| y <= x));
+                v.insert(index, x);
+            }
+            for x in [3, 8, 11, 0, 3] {
+                let expected = v.iter().position(|&y| y == x).map(|i| v.remove(i));
+                assert_eq!(mt.remove_sorted(&x), expected);
+            }
+            assert!(mt.iter().eq(v.iter()));
+            for x in -1..12 {
+                assert_eq!(mt.lower_bound(&x), v.partition_point(|&y| y < x));
+                assert_eq!(mt.upper_bound(&x), v.partition_point(|&y| y <= x));
+                assert_eq!(mt.rank(&x), v.iter().filter(|&&y| y < x).count());
+            }
+            let (l, r) = mt.split_by_key(&5);
+            assert!(l.iter().eq(v.iter().filter(|&&y| y < 5)));
+            assert!(r.iter().eq(v.iter().filter(|&&y| y >= 5)));
+        }
+    }
+
+    #[test]
+    fn ordered_sum() {
+        use crate::monoids::OrderedSum;
+
+        let mut rng = Rng::new();
+        for _ in 0..100 {
+            let mut mt = MasterTree::<OrderedSum<i64>>::new();
+            mt.2 = rng.make();
+            let mut v = vec![];
+            for i in 0..30 {
+                let x = i * 7 % 11;
+                let index = mt.insert_sorted(x);
+                v.insert(index, x);
+                assert_eq!(mt.prod(..), v.iter().sum());
+            }
+            for l in 0..=v.len() {
+                for r in l..=v.len() {
+                    assert_eq!(mt.prod(l..r), v[l..r].iter().sum());
+                }
+            }
+            assert_eq!(mt.remove_sorted(&4), Some(4));
+            v.remove(v.iter().position(|&y| y == 4).unwrap());
+            let (l, r) = mt.split_by_key(&5);
+            assert_eq!(l.prod(..), v.iter().filter(|&&y| y < 5).sum());
+            assert_eq!(r.prod(..), v.iter().filter(|&&y| y >= 5).sum());
+            let k = r.lower_bound(&8);
+            assert_eq!(r.prod(k..), v.iter().filter(|&&y| y >= 8).sum());
+            let mut mt = l.merge(r);
+            mt.insert_sorted(100);
+            assert_eq!(mt.prod(..), v.iter().sum::<i64>() + 100);
+        }
+    }
+
+    #[test]
+    fn seed() {
+        use crate::monoids::RangeAddRangeSum;
+
+        let build = |mut mt: MasterTree<RangeAddRangeSum<i64>>| {
+            for i in 0..50 {
+                mt.insert(i as usize / 3, i);
+            }
+            mt
+        };
+        let a = build(MasterTree::with_seed(0));
+        let b = build(MasterTree::with_seed(0));
+        assert_eq!(format!("{a:?}"), format!("{b:?}"));
+        let c = build(MasterTree::with_random_seed());
+        assert!(a.iter().eq(c.iter()));
+
+        let mut mt = a.merge(c);
+        assert_eq!(mt.len(), 100);
+        assert_eq!(mt.prod(..), 2 * (0..50).sum::<i64>());
+        mt.remove(10);
+        mt.insert(20, 5);
+        assert_eq!(mt.len(), 100);
+    }
+
+    #[test]
+    fn bulk() {
+        use crate::monoids::RangeAddRangeSum;
+
+        let mut mt = (0..100).collect::<MasterTree<RangeAddRangeSum<i64>>>();
+        assert_eq!(mt.len(), 100);
+        assert_eq!(mt.prod(10..20), (10..20).sum());
+        mt.extend(100..150);
+        mt.reverse();
+        mt.apply(..50, &1);
+        let expected = (0..150)
+            .rev()
+            .enumerate()
+            .map(|(i, x)| x + i64::from(i < 50))
+            .collect::<Vec<_>>();
+        assert_eq!(mt.to_vec(), expected);
+        let shared = mt.clone();
+        assert_eq!(mt.into_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(shared.into_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(
+            MasterTree::<RangeAddRangeSum<i64>>::from_vec(vec![]).to_vec(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn copy_range() {
+        use crate::monoids::RangeAddRangeSum;
+
+        let mut mt = (0..5).collect::<MasterTree<RangeAddRangeSum<i64>>>();
+        let mut v = (0..5).collect::<Vec<i64>>();
+        for (l, r, i) in [(1, 4, 0), (0, 8, 8), (3, 10, 2), (5, 6, 17)] {
+            mt.copy_range(l..r, i);
+            let copy = v[l..r].to_vec();
+            v.splice(i..i, copy);
+        }
+        assert_eq!(mt.to_vec(), v);
+        assert_eq!(mt.slice(4..15).to_vec(), v[4..15]);
+
+        for _ in 0..50 {
+            let len = mt.len();
+            mt.copy_range(.., len / 3);
+        }
+        assert_eq!(mt.len(), v.len() << 50);
+        assert_eq!(mt.prod(..), v.iter().sum::<i64>() << 50);
+        let s = mt.slice(..1000);
+        assert_eq!(s.len(), 1000);
+    }
+
+    #[test]
+    fn rebuild() {
+        use crate::monoids::RangeAddRangeSum;
+
+        let mut mt = (0..10).collect::<MasterTree<RangeAddRangeSum<i64>>>();
+        assert_eq!(mt.node_count(), 10);
+        for _ in 0..10 {
+            let len = mt.len();
+            mt.copy_range(..len / 2, len);
+        }
+        let len = mt.len();
+        assert!(mt.node_count() < len);
+        let snapshot = mt.clone();
+        let expected = mt.to_vec();
+        mt.apply(3..7, &1);
+        mt.rebuild();
+        assert_eq!(mt.node_count(), len);
+        assert_eq!(mt.prod(..), expected.iter().sum::<i64>() + 4);
+        assert_eq!(snapshot.to_vec(), expected);
+    }
+
+    #[test]
+    fn range() {
+        use crate::monoids::RangeAddRangeSum;
+
+        let mut rng = Rng::new();
+        for _ in 0..20 {
+            let mut mt = MasterTree::<RangeAddRangeSum<i64>>::new();
+            mt.2 = rng.make();
+            let mut v = vec![];
+            for i in 0..20 {
+                mt.insert(i as usize / 2, i);
+                v.insert(i as usize / 2, i);
+            }
+            mt.apply(4..15, &100);
+            mt.reverse();
+            mt.apply(..7, &1000);
+            for x in &mut v[4..15] {
+                *x += 100;
+            }
+            v.reverse();
+            for x in &mut v[..7] {
+                *x += 1000;
+            }
+            assert!(mt.iter().eq(v.iter()));
+            for l in 0..=v.len() {
+                for r in l..=v.len() {
+                    assert!(mt.range(l..r).eq(v[l..r].iter()));
+                    assert!(mt.range(l..r).rev().eq(v[l..r].iter().rev()));
+                    assert_eq!(mt.range(l..r).len(), r - l);
+                }
+            }
+            let mut it = mt.range(3..17);
+            let mut expected = v[3..17].iter();
+            while let Some(x) = it.next() {
+                assert_eq!(Some(x), expected.next());
+                assert_eq!(it.next_back(), expected.next_back());
+            }
+            assert_eq!(expected.next(), None);
+            drop(it);
+
+            *mt.setter(5) += 7;
+            v[5] += 7;
+            mt.set(0, -1);
+            v[0] = -1;
+            std::mem::forget(mt.range_mut(4..12));
+            assert_eq!(mt.to_vec(), v);
+            for x in mt.range_mut(9..16).iter_mut() {
+                *x *= 2;
+            }
+            for x in &mut v[9..16] {
+                *x *= 2;
+            }
+            assert_eq!(mt.to_vec(), v);
+            assert_eq!(mt.prod(2..12), v[2..12].iter().sum());
+        }
+    }
+
+    #[test]
+    fn editing() {
+        use crate::monoids::RangeAddRangeSum;
+
+        let mut mt = (0..20).collect::<MasterTree<RangeAddRangeSum<i64>>>();
+        let mut v = (0..20).collect::<Vec<i64>>();
+        mt.reverse_range(3..11);
+        v[3..11].reverse();
+        assert_eq!(mt.to_vec(), v);
+        mt.rotate_left(5..17, 4);
+        v[5..17].rotate_left(4);
+        assert_eq!(mt.to_vec(), v);
+        mt.move_range(2..6, 12);
+        let m = v.drain(2..6).collect::<Vec<_>>();
+        v.splice(12..12, m);
+        assert_eq!(mt.to_vec(), v);
+        mt.swap_ranges(14..19, 1..3);
+        let w = [&v[..1], &v[14..19], &v[3..14], &v[1..3], &v[19..]].concat();
+        assert_eq!(mt.to_vec(), w);
+        assert_eq!(mt.prod(..), w.iter().sum());
+    }
+
+    #[test]
+    fn reverse_info() {
+        // 区間の値の列そのものを情報として持つ, 反転で情報が変わるマネージャ
+        struct M;
+        impl MasterManager for M {
+            type T = i32;
+            type Info = Vec<i32>;
+            type Prod = Vec<i32>;
+            type Lazy = ();
+            fn make_info(
+                left: (Option<&Vec<i32>>, usize),
+                mid: &i32,
+                right: (Option<&Vec<i32>>, usize),
+            ) -> Vec<i32> {
+                let mut info = left.0.cloned().unwrap_or_default();
+                info.push(*mid);
+                info.extend(right.0.into_iter().flatten());
+                info
+            }
+            fn rev(info: &mut Vec<i32>, _: usize) {
+                info.reverse();
+            }
+            fn apply_info(_: &mut Vec<i32>, _: usize, _: &()) {}
+            fn apply_val(_: &mut i32, _: &()) {}
+            fn propagate(
+                _: &mut Vec<i32>,
+                _: (Option<&mut Vec<i32>>, usize),
+                _: &mut i32,
+                _: (Option<&mut Vec<i32>>, usize),
+            ) {
+            }
+            fn info2prod(info: &Vec<i32>) -> Vec<i32> {
+                info.clone()
+            }
+            fn val2prod(val: &i32) -> Vec<i32> {
+                vec![*val]
+            }
+            fn e() -> Vec<i32> {
+                vec![]
+            }
+            fn op(mut left: Vec<i32>, right: Vec<i32>) -> Vec<i32> {
+                left.extend(right);
+                left
+            }
+        }
+
+        let mut rng = Rng::new();
+        for _ in 0..50 {
+            let mut mt = MasterTree::<M>::new();
+            mt.2 = rng.make();
+            mt.extend(0..20);
+            let mut v = (0..20).collect::<Vec<i32>>();
+            for i in 0..30 {
+                let l = i * 7 % 20;
+                let r = l + 1 + i * 11 % (20 - l);
+                if i % 3 == 0 {
+                    mt.reverse();
+                    v.reverse();
+                }
+                mt.reverse_range(l..r);
+                v[l..r].reverse();
+                mt.rotate_left(l..r, i % (r - l));
+                v[l..r].rotate_left(i % (r - l));
+                assert_eq!(mt.to_vec(), v);
+                assert_eq!(mt.prod(..), v);
+                assert_eq!(mt.prod(l..r), v[l..r]);
+            }
+        }
+    }
 }
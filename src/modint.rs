@@ -1,3 +1,4 @@
+use super::util::{HasOne, HasZero};
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 const fn phi_const(mut n: u32) -> u32 {
@@ -215,6 +216,17 @@ macro_rules! impl_cast_int {
 }
 impl_cast_int! { i8, i16, i32, i64, i128, isize }
 
+impl<const N: u32> HasZero for ModInt<N> {
+    fn zero() -> Self {
+        Self(0)
+    }
+}
+impl<const N: u32> HasOne for ModInt<N> {
+    fn one() -> Self {
+        Self(1 % N)
+    }
+}
+
 impl<const N: u32> std::fmt::Debug for ModInt<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
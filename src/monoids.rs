@@ -0,0 +1,850 @@
+//! よく使うモノイド・作用付きモノイドの実装集
+
+use super::lazysegtree::LazyMonoid;
+use super::mastertree::{MasterManager, OrderedMasterManager};
+use super::util::{
+    Associativity, Commutativity, HasMax, HasMin, HasOne, HasZero, Idempotence, Identity, Inverse,
+    Magma,
+};
+use std::marker::PhantomData;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Mul, Neg, Not, Rem};
+
+/// 型引数だけを持つ構造体を定義するマクロ
+macro_rules! marker {
+    ($($(#[$attr:meta])* $name:ident<$($g:ident $(: $b:path)?),*>;)*) => {$(
+        $(#[$attr])*
+        pub struct $name<$($g $(: $b)?),*>(PhantomData<fn() -> ($($g,)*)>);
+        impl<$($g $(: $b)?),*> $name<$($g),*> {
+            /// 新しく作る
+            #[must_use]
+            pub fn new() -> Self {
+                Self(PhantomData)
+            }
+        }
+        impl<$($g $(: $b)?),*> Clone for $name<$($g),*> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+        impl<$($g $(: $b)?),*> Copy for $name<$($g),*> {}
+        impl<$($g $(: $b)?),*> Default for $name<$($g),*> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+        impl<$($g $(: $b)?),*> std::fmt::Debug for $name<$($g),*> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(stringify!($name))
+            }
+        }
+    )*};
+}
+
+marker! {
+    /// 加法のモノイド
+    Sum<T>;
+    /// 乗法のモノイド
+    Product<T>;
+    /// 最小値のモノイド
+    Min<T>;
+    /// 最大値のモノイド
+    Max<T>;
+    /// 排他的論理和のモノイド
+    Xor<T>;
+    /// 最大公約数のモノイド
+    ///
+    /// 非負の値のみを扱う
+    Gcd<T>;
+    /// 論理和のモノイド
+    Or<T>;
+    /// 論理積のモノイド
+    And<T>;
+    /// 一次関数の合成のモノイド
+    ///
+    /// `(a, b)`は`x ↦ a x + b`を表し, 区間の総積は左の関数から順に適用した関数になる
+    Affine<T>;
+    /// 最小値とその位置のモノイド
+    ///
+    /// 最小値が複数ある場合は位置が最も小さいものを選ぶ
+    MinWithIndex<T>;
+    /// 最大部分配列和のモノイド
+    ///
+    /// 値は`Some((総和, 接頭辞の最大値, 接尾辞の最大値, 部分配列の最大値))`で, 単位元は`None`である.
+    /// 空の部分配列は考えない.
+    MaxSubarraySum<T>;
+    /// 区間加算・区間和の作用付きモノイド
+    ///
+    /// LazySegmentTreeでは値は`(区間和, 区間の長さ)`である
+    RangeAddRangeSum<T>;
+    /// 区間代入・区間最小値の作用付きモノイド
+    RangeAssignRangeMin<T>;
+    /// 区間アフィン変換・区間和の作用付きモノイド
+    ///
+    /// 作用素`(a, b)`は`x ↦ a x + b`を表す.
+    /// LazySegmentTreeでは値は`(区間和, 区間の長さ)`である
+    RangeAffineRangeSum<T>;
+    /// 区間加算・区間最小値の作用付きモノイド
+    ///
+    /// LazySegmentTreeでは値は`Some(区間最小値)`で, 単位元は`None`である
+    RangeAddRangeMin<T>;
+    /// 値を大小の順に整列した列として扱うMasterManager
+    ///
+    /// 区間の情報も作用素も持たない
+    Ordered<T>;
+    /// 値を大小の順に整列した列として扱い, 区間和を持つMasterManager
+    ///
+    /// 作用素は持たない
+    OrderedSum<T>;
+}
+
+/// `N`×`N`行列の積のモノイド
+pub struct MatrixProduct<T, const N: usize>(PhantomData<fn() -> T>);
+impl<T, const N: usize> MatrixProduct<T, N> {
+    /// 新しく作る
+    #[must_use]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+impl<T, const N: usize> Clone for MatrixProduct<T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T, const N: usize> Copy for MatrixProduct<T, N> {}
+impl<T, const N: usize> Default for MatrixProduct<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> std::fmt::Debug for MatrixProduct<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MatrixProduct")
+    }
+}
+
+impl<T: Copy + Add<Output = T>> Magma for Sum<T> {
+    type T = T;
+    fn op(&self, &lhs: &T, &rhs: &T) -> T {
+        lhs + rhs
+    }
+}
+impl<T: Copy + Add<Output = T> + HasZero> Identity for Sum<T> {
+    fn e(&self) -> T {
+        T::zero()
+    }
+}
+impl<T: Copy + Add<Output = T> + Neg<Output = T>> Inverse for Sum<T> {
+    fn inv(&self, &v: &T) -> T {
+        -v
+    }
+}
+impl<T: Copy + Add<Output = T>> Associativity for Sum<T> {}
+impl<T: Copy + Add<Output = T>> Commutativity for Sum<T> {}
+
+impl<T: Copy + Mul<Output = T>> Magma for Product<T> {
+    type T = T;
+    fn op(&self, &lhs: &T, &rhs: &T) -> T {
+        lhs * rhs
+    }
+}
+impl<T: Copy + Mul<Output = T> + HasOne> Identity for Product<T> {
+    fn e(&self) -> T {
+        T::one()
+    }
+}
+impl<T: Copy + Mul<Output = T>> Associativity for Product<T> {}
+impl<T: Copy + Mul<Output = T>> Commutativity for Product<T> {}
+
+impl<T: Copy + Ord> Magma for Min<T> {
+    type T = T;
+    fn op(&self, &lhs: &T, &rhs: &T) -> T {
+        lhs.min(rhs)
+    }
+}
+impl<T: Copy + Ord + HasMax> Identity for Min<T> {
+    fn e(&self) -> T {
+        T::max_value()
+    }
+}
+impl<T: Copy + Ord> Associativity for Min<T> {}
+impl<T: Copy + Ord> Commutativity for Min<T> {}
+impl<T: Copy + Ord> Idempotence for Min<T> {}
+
+impl<T: Copy + Ord> Magma for Max<T> {
+    type T = T;
+    fn op(&self, &lhs: &T, &rhs: &T) -> T {
+        lhs.max(rhs)
+    }
+}
+impl<T: Copy + Ord + HasMin> Identity for Max<T> {
+    fn e(&self) -> T {
+        T::min_value()
+    }
+}
+impl<T: Copy + Ord> Associativity for Max<T> {}
+impl<T: Copy + Ord> Commutativity for Max<T> {}
+impl<T: Copy + Ord> Idempotence for Max<T> {}
+
+impl<T: Copy + BitXor<Output = T>> Magma for Xor<T> {
+    type T = T;
+    fn op(&self, &lhs: &T, &rhs: &T) -> T {
+        lhs ^ rhs
+    }
+}
+impl<T: Copy + BitXor<Output = T> + HasZero> Identity for Xor<T> {
+    fn e(&self) -> T {
+        T::zero()
+    }
+}
+impl<T: Copy + BitXor<Output = T>> Inverse for Xor<T> {
+    fn inv(&self, &v: &T) -> T {
+        v
+    }
+}
+impl<T: Copy + BitXor<Output = T>> Associativity for Xor<T> {}
+impl<T: Copy + BitXor<Output = T>> Commutativity for Xor<T> {}
+
+impl<T: Copy + PartialEq + Rem<Output = T> + HasZero> Magma for Gcd<T> {
+    type T = T;
+    fn op(&self, &lhs: &T, &rhs: &T) -> T {
+        let (mut a, mut b) = (lhs, rhs);
+        while b != T::zero() {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+}
+impl<T: Copy + PartialEq + Rem<Output = T> + HasZero> Identity for Gcd<T> {
+    fn e(&self) -> T {
+        T::zero()
+    }
+}
+impl<T: Copy + PartialEq + Rem<Output = T> + HasZero> Associativity for Gcd<T> {}
+impl<T: Copy + PartialEq + Rem<Output = T> + HasZero> Commutativity for Gcd<T> {}
+impl<T: Copy + PartialEq + Rem<Output = T> + HasZero> Idempotence for Gcd<T> {}
+
+impl<T: Copy + BitOr<Output = T>> Magma for Or<T> {
+    type T = T;
+    fn op(&self, &lhs: &T, &rhs: &T) -> T {
+        lhs | rhs
+    }
+}
+impl<T: Copy + BitOr<Output = T> + HasZero> Identity for Or<T> {
+    fn e(&self) -> T {
+        T::zero()
+    }
+}
+impl<T: Copy + BitOr<Output = T>> Associativity for Or<T> {}
+impl<T: Copy + BitOr<Output = T>> Commutativity for Or<T> {}
+impl<T: Copy + BitOr<Output = T>> Idempotence for Or<T> {}
+
+impl<T: Copy + BitAnd<Output = T>> Magma for And<T> {
+    type T = T;
+    fn op(&self, &lhs: &T, &rhs: &T) -> T {
+        lhs & rhs
+    }
+}
+impl<T: Copy + BitAnd<Output = T> + Not<Output = T> + HasZero> Identity for And<T> {
+    fn e(&self) -> T {
+        !T::zero()
+    }
+}
+impl<T: Copy + BitAnd<Output = T>> Associativity for And<T> {}
+impl<T: Copy + BitAnd<Output = T>> Commutativity for And<T> {}
+impl<T: Copy + BitAnd<Output = T>> Idempotence for And<T> {}
+
+impl<T: Copy + Add<Output = T> + Mul<Output = T>> Magma for Affine<T> {
+    type T = (T, T);
+    fn op(&self, &(a, b): &(T, T), &(c, d): &(T, T)) -> (T, T) {
+        (c * a, c * b + d)
+    }
+}
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + HasZero + HasOne> Identity for Affine<T> {
+    fn e(&self) -> (T, T) {
+        (T::one(), T::zero())
+    }
+}
+impl<T: Copy + Add<Output = T> + Mul<Output = T>> Associativity for Affine<T> {}
+
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + HasZero, const N: usize> Magma
+    for MatrixProduct<T, N>
+{
+    type T = [[T; N]; N];
+    fn op(&self, lhs: &[[T; N]; N], rhs: &[[T; N]; N]) -> [[T; N]; N] {
+        let mut r = [[T::zero(); N]; N];
+        for (r, lhs) in r.iter_mut().zip(lhs) {
+            for (&l, rhs) in lhs.iter().zip(rhs) {
+                for (r, &x) in r.iter_mut().zip(rhs) {
+                    *r = *r + l * x;
+                }
+            }
+        }
+        r
+    }
+}
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + HasZero + HasOne, const N: usize> Identity
+    for MatrixProduct<T, N>
+{
+    fn e(&self) -> [[T; N]; N] {
+        let mut r = [[T::zero(); N]; N];
+        for (i, r) in r.iter_mut().enumerate() {
+            r[i] = T::one();
+        }
+        r
+    }
+}
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + HasZero, const N: usize> Associativity
+    for MatrixProduct<T, N>
+{
+}
+
+impl<T: Copy + Ord> Magma for MinWithIndex<T> {
+    type T = (T, usize);
+    fn op(&self, &lhs: &(T, usize), &rhs: &(T, usize)) -> (T, usize) {
+        lhs.min(rhs)
+    }
+}
+impl<T: Copy + Ord + HasMax> Identity for MinWithIndex<T> {
+    fn e(&self) -> (T, usize) {
+        (T::max_value(), usize::MAX)
+    }
+}
+impl<T: Copy + Ord> Associativity for MinWithIndex<T> {}
+impl<T: Copy + Ord> Commutativity for MinWithIndex<T> {}
+impl<T: Copy + Ord> Idempotence for MinWithIndex<T> {}
+
+impl<T: Copy> MaxSubarraySum<T> {
+    /// 長さ1の列`[x]`に対応する値を返す
+    #[must_use]
+    pub fn single(x: T) -> Option<(T, T, T, T)> {
+        Some((x, x, x, x))
+    }
+}
+impl<T: Copy + Ord + Add<Output = T>> Magma for MaxSubarraySum<T> {
+    type T = Option<(T, T, T, T)>;
+    fn op(&self, &lhs: &Self::T, &rhs: &Self::T) -> Self::T {
+        let (Some(l), Some(r)) = (lhs, rhs) else {
+            return lhs.or(rhs);
+        };
+        Some((
+            l.0 + r.0,
+            l.1.max(l.0 + r.1),
+            (l.2 + r.0).max(r.2),
+            l.3.max(r.3).max(l.2 + r.1),
+        ))
+    }
+}
+impl<T: Copy + Ord + Add<Output = T>> Identity for MaxSubarraySum<T> {
+    fn e(&self) -> Self::T {
+        None
+    }
+}
+impl<T: Copy + Ord + Add<Output = T>> Associativity for MaxSubarraySum<T> {}
+
+impl<T: Copy + Add<Output = T>> Magma for RangeAddRangeSum<T> {
+    type T = (T, T);
+    fn op(&self, &(a, b): &(T, T), &(c, d): &(T, T)) -> (T, T) {
+        (a + c, b + d)
+    }
+}
+impl<T: Copy + Add<Output = T> + HasZero> Identity for RangeAddRangeSum<T> {
+    fn e(&self) -> (T, T) {
+        (T::zero(), T::zero())
+    }
+}
+impl<T: Copy + Add<Output = T>> Associativity for RangeAddRangeSum<T> {}
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + HasZero> LazyMonoid for RangeAddRangeSum<T> {
+    type F = T;
+    fn id(&self) -> T {
+        T::zero()
+    }
+    fn comp(&self, &g: &T, &f: &T) -> T {
+        g + f
+    }
+    fn map(&self, &f: &T, &(sum, len): &(T, T)) -> (T, T) {
+        (sum + f * len, len)
+    }
+}
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + HasZero + HasOne> MasterManager
+    for RangeAddRangeSum<T>
+{
+    type T = T;
+    /// (区間和, 区間の長さ, 遅延している作用素)
+    type Info = (T, T, T);
+    type Prod = T;
+    type Lazy = T;
+
+    fn make_info(
+        left: (Option<&(T, T, T)>, usize),
+        &mid: &T,
+        right: (Option<&(T, T, T)>, usize),
+    ) -> (T, T, T) {
+        let (ls, ll) = left.0.map_or((T::zero(), T::zero()), |v| (v.0, v.1));
+        let (rs, rl) = right.0.map_or((T::zero(), T::zero()), |v| (v.0, v.1));
+        (ls + mid + rs, ll + T::one() + rl, T::zero())
+    }
+    fn rev(_: &mut (T, T, T), _: usize) {}
+    fn apply_info(info: &mut (T, T, T), _: usize, &lazy: &T) {
+        info.0 = info.0 + lazy * info.1;
+        info.2 = info.2 + lazy;
+    }
+    fn apply_val(val: &mut T, &lazy: &T) {
+        *val = *val + lazy;
+    }
+    fn propagate(
+        info: &mut (T, T, T),
+        left: (Option<&mut (T, T, T)>, usize),
+        val: &mut T,
+        right: (Option<&mut (T, T, T)>, usize),
+    ) {
+        let lazy = std::mem::replace(&mut info.2, T::zero());
+        Self::apply_val(val, &lazy);
+        if let Some(left) = left.0 {
+            Self::apply_info(left, 0, &lazy);
+        }
+        if let Some(right) = right.0 {
+            Self::apply_info(right, 0, &lazy);
+        }
+    }
+    fn info2prod(info: &(T, T, T)) -> T {
+        info.0
+    }
+    fn val2prod(&val: &T) -> T {
+        val
+    }
+    fn e() -> T {
+        T::zero()
+    }
+    fn op(left: T, right: T) -> T {
+        left + right
+    }
+}
+
+impl<T: Copy + Ord> Magma for RangeAssignRangeMin<T> {
+    type T = T;
+    fn op(&self, &lhs: &T, &rhs: &T) -> T {
+        lhs.min(rhs)
+    }
+}
+impl<T: Copy + Ord + HasMax> Identity for RangeAssignRangeMin<T> {
+    fn e(&self) -> T {
+        T::max_value()
+    }
+}
+impl<T: Copy + Ord> Associativity for RangeAssignRangeMin<T> {}
+impl<T: Copy + Ord + HasMax> LazyMonoid for RangeAssignRangeMin<T> {
+    type F = Option<T>;
+    fn id(&self) -> Option<T> {
+        None
+    }
+    fn comp(&self, &g: &Option<T>, &f: &Option<T>) -> Option<T> {
+        g.or(f)
+    }
+    fn map(&self, &f: &Option<T>, &x: &T) -> T {
+        f.unwrap_or(x)
+    }
+}
+impl<T: Copy + Ord + HasMax> MasterManager for RangeAssignRangeMin<T> {
+    type T = T;
+    /// (区間最小値, 遅延している代入)
+    type Info = (T, Option<T>);
+    type Prod = T;
+    type Lazy = T;
+
+    fn make_info(
+        left: (Option<&(T, Option<T>)>, usize),
+        &mid: &T,
+        right: (Option<&(T, Option<T>)>, usize),
+    ) -> (T, Option<T>) {
+        let l = left.0.map_or(mid, |v| v.0);
+        let r = right.0.map_or(mid, |v| v.0);
+        (l.min(mid).min(r), None)
+    }
+    fn rev(_: &mut (T, Option<T>), _: usize) {}
+    fn apply_info(info: &mut (T, Option<T>), _: usize, &lazy: &T) {
+        *info = (lazy, Some(lazy));
+    }
+    fn apply_val(val: &mut T, &lazy: &T) {
+        *val = lazy;
+    }
+    fn propagate(
+        info: &mut (T, Option<T>),
+        left: (Option<&mut (T, Option<T>)>, usize),
+        val: &mut T,
+        right: (Option<&mut (T, Option<T>)>, usize),
+    ) {
+        let Some(lazy) = info.1.take() else {
+            return;
+        };
+        *val = lazy;
+        if let Some(left) = left.0 {
+            *left = (lazy, Some(lazy));
+        }
+        if let Some(right) = right.0 {
+            *right = (lazy, Some(lazy));
+        }
+    }
+    fn info2prod(info: &(T, Option<T>)) -> T {
+        info.0
+    }
+    fn val2prod(&val: &T) -> T {
+        val
+    }
+    fn e() -> T {
+        T::max_value()
+    }
+    fn op(left: T, right: T) -> T {
+        left.min(right)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> Magma for RangeAffineRangeSum<T> {
+    type T = (T, T);
+    fn op(&self, &(a, b): &(T, T), &(c, d): &(T, T)) -> (T, T) {
+        (a + c, b + d)
+    }
+}
+impl<T: Copy + Add<Output = T> + HasZero> Identity for RangeAffineRangeSum<T> {
+    fn e(&self) -> (T, T) {
+        (T::zero(), T::zero())
+    }
+}
+impl<T: Copy + Add<Output = T>> Associativity for RangeAffineRangeSum<T> {}
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + HasZero + HasOne> LazyMonoid
+    for RangeAffineRangeSum<T>
+{
+    type F = (T, T);
+    fn id(&self) -> (T, T) {
+        (T::one(), T::zero())
+    }
+    fn comp(&self, &(c, d): &(T, T), &(a, b): &(T, T)) -> (T, T) {
+        (c * a, c * b + d)
+    }
+    fn map(&self, &(a, b): &(T, T), &(sum, len): &(T, T)) -> (T, T) {
+        (a * sum + b * len, len)
+    }
+}
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + HasZero + HasOne> MasterManager
+    for RangeAffineRangeSum<T>
+{
+    type T = T;
+    /// (区間和, 区間の長さ, 遅延している作用素)
+    type Info = (T, T, (T, T));
+    type Prod = T;
+    type Lazy = (T, T);
+
+    fn make_info(
+        left: (Option<&(T, T, (T, T))>, usize),
+        &mid: &T,
+        right: (Option<&(T, T, (T, T))>, usize),
+    ) -> (T, T, (T, T)) {
+        let (ls, ll) = left.0.map_or((T::zero(), T::zero()), |v| (v.0, v.1));
+        let (rs, rl) = right.0.map_or((T::zero(), T::zero()), |v| (v.0, v.1));
+        (ls + mid + rs, ll + T::one() + rl, (T::one(), T::zero()))
+    }
+    fn rev(_: &mut (T, T, (T, T)), _: usize) {}
+    fn apply_info(info: &mut (T, T, (T, T)), _: usize, &(a, b): &(T, T)) {
+        info.0 = a * info.0 + b * info.1;
+        info.2 = (a * info.2 .0, a * info.2 .1 + b);
+    }
+    fn apply_val(val: &mut T, &(a, b): &(T, T)) {
+        *val = a * *val + b;
+    }
+    fn propagate(
+        info: &mut (T, T, (T, T)),
+        left: (Option<&mut (T, T, (T, T))>, usize),
+        val: &mut T,
+        right: (Option<&mut (T, T, (T, T))>, usize),
+    ) {
+        let lazy = std::mem::replace(&mut info.2, (T::one(), T::zero()));
+        Self::apply_val(val, &lazy);
+        if let Some(left) = left.0 {
+            Self::apply_info(left, 0, &lazy);
+        }
+        if let Some(right) = right.0 {
+            Self::apply_info(right, 0, &lazy);
+        }
+    }
+    fn info2prod(info: &(T, T, (T, T))) -> T {
+        info.0
+    }
+    fn val2prod(&val: &T) -> T {
+        val
+    }
+    fn e() -> T {
+        T::zero()
+    }
+    fn op(left: T, right: T) -> T {
+        left + right
+    }
+}
+
+impl<T: Copy + Ord> Magma for RangeAddRangeMin<T> {
+    type T = Option<T>;
+    fn op(&self, &lhs: &Option<T>, &rhs: &Option<T>) -> Option<T> {
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => Some(lhs.min(rhs)),
+            _ => lhs.or(rhs),
+        }
+    }
+}
+impl<T: Copy + Ord> Identity for RangeAddRangeMin<T> {
+    fn e(&self) -> Option<T> {
+        None
+    }
+}
+impl<T: Copy + Ord> Associativity for RangeAddRangeMin<T> {}
+impl<T: Copy + Ord + Add<Output = T> + HasZero> LazyMonoid for RangeAddRangeMin<T> {
+    type F = T;
+    fn id(&self) -> T {
+        T::zero()
+    }
+    fn comp(&self, &g: &T, &f: &T) -> T {
+        g + f
+    }
+    fn map(&self, &f: &T, &x: &Option<T>) -> Option<T> {
+        x.map(|x| x + f)
+    }
+}
+impl<T: Copy + Ord + Add<Output = T> + HasZero + HasMax> MasterManager for RangeAddRangeMin<T> {
+    type T = T;
+    /// (区間最小値, 遅延している作用素)
+    type Info = (T, T);
+    type Prod = T;
+    type Lazy = T;
+
+    fn make_info(
+        left: (Option<&(T, T)>, usize),
+        &mid: &T,
+        right: (Option<&(T, T)>, usize),
+    ) -> (T, T) {
+        let l = left.0.map_or(mid, |v| v.0);
+        let r = right.0.map_or(mid, |v| v.0);
+        (l.min(mid).min(r), T::zero())
+    }
+    fn rev(_: &mut (T, T), _: usize) {}
+    fn apply_info(info: &mut (T, T), _: usize, &lazy: &T) {
+        info.0 = info.0 + lazy;
+        info.1 = info.1 + lazy;
+    }
+    fn apply_val(val: &mut T, &lazy: &T) {
+        *val = *val + lazy;
+    }
+    fn propagate(
+        info: &mut (T, T),
+        left: (Option<&mut (T, T)>, usize),
+        val: &mut T,
+        right: (Option<&mut (T, T)>, usize),
+    ) {
+        let lazy = std::mem::replace(&mut info.1, T::zero());
+        Self::apply_val(val, &lazy);
+        if let Some(left) = left.0 {
+            Self::apply_info(left, 0, &lazy);
+        }
+        if let Some(right) = right.0 {
+            Self::apply_info(right, 0, &lazy);
+        }
+    }
+    fn info2prod(info: &(T, T)) -> T {
+        info.0
+    }
+    fn val2prod(&val: &T) -> T {
+        val
+    }
+    fn e() -> T {
+        T::max_value()
+    }
+    fn op(left: T, right: T) -> T {
+        left.min(right)
+    }
+}
+
+impl<T: Ord + Clone> MasterManager for Ordered<T> {
+    type T = T;
+    type Info = ();
+    type Prod = ();
+    type Lazy = ();
+
+    fn make_info(_: (Option<&()>, usize), _: &T, _: (Option<&()>, usize)) {}
+    fn rev(_: &mut (), _: usize) {}
+    fn apply_info(_: &mut (), _: usize, _: &()) {}
+    fn apply_val(_: &mut T, _: &()) {}
+    fn propagate(_: &mut (), _: (Option<&mut ()>, usize), _: &mut T, _: (Option<&mut ()>, usize)) {}
+    fn info2prod(_: &()) {}
+    fn val2prod(_: &T) {}
+    fn e() {}
+    fn op(_: (), _: ()) {}
+}
+impl<T: Ord + Clone> OrderedMasterManager for Ordered<T> {
+    fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering {
+        lhs.cmp(rhs)
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + HasZero> MasterManager for OrderedSum<T> {
+    type T = T;
+    /// 区間和
+    type Info = T;
+    type Prod = T;
+    type Lazy = ();
+
+    fn make_info(left: (Option<&T>, usize), &mid: &T, right: (Option<&T>, usize)) -> T {
+        left.0.copied().unwrap_or_else(T::zero) + mid + right.0.copied().unwrap_or_else(T::zero)
+    }
+    fn rev(_: &mut T, _: usize) {}
+    fn apply_info(_: &mut T, _: usize, _: &()) {}
+    fn apply_val(_: &mut T, _: &()) {}
+    fn propagate(_: &mut T, _: (Option<&mut T>, usize), _: &mut T, _: (Option<&mut T>, usize)) {}
+    fn info2prod(&info: &T) -> T {
+        info
+    }
+    fn val2prod(&val: &T) -> T {
+        val
+    }
+    fn e() -> T {
+        T::zero()
+    }
+    fn op(left: T, right: T) -> T {
+        left + right
+    }
+}
+impl<T: Copy + Ord + Add<Output = T> + HasZero> OrderedMasterManager for OrderedSum<T> {
+    fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering {
+        lhs.cmp(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binaryindexedtree::BinaryIndexedTree;
+    use crate::lazysegtree::LazySegmentTree;
+    use crate::mastertree::MasterTree;
+    use crate::modint::ModInt;
+    use crate::segmenttree::SegmentTree;
+    use crate::sparsetable::SparseTable;
+
+    #[test]
+    fn monoids() {
+        let v = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+        assert_eq!(SegmentTree::from_vec(Sum::new(), v.clone()).prod(2..6), 19);
+        assert_eq!(
+            SegmentTree::from_vec(Product::new(), v.clone()).prod(2..6),
+            180
+        );
+        assert_eq!(SparseTable::new(Min::new(), v.clone()).query(4..), 2);
+        assert_eq!(SparseTable::new(Max::new(), v.clone()).query(..5), 5);
+        assert_eq!(SegmentTree::from_vec(Xor::new(), v.clone()).prod(..3), 6);
+        assert_eq!(SparseTable::new(Gcd::new(), [12, 18, 30, 7]).query(..3), 6);
+        assert_eq!(SparseTable::new(Or::new(), v.clone()).query(2..5), 5);
+        assert_eq!(SparseTable::new(And::new(), [7u8, 6, 14]).query(..), 6);
+
+        let mut bit = BinaryIndexedTree::new(Sum::new(), 5);
+        bit.add(1, &3);
+        bit.add(3, &-2);
+        assert_eq!(bit.sum(..), 1);
+        assert_eq!(bit.sum(2..), -2);
+
+        // x ↦ 2x + 1, x ↦ 3x, x ↦ x + 4
+        let seg = SegmentTree::from_vec(Affine::new(), vec![(2, 1), (3, 0), (1, 4)]);
+        assert_eq!(seg.prod(..), (6, 7));
+        assert_eq!(seg.prod(1..), (3, 4));
+
+        let fib = SegmentTree::from_vec(MatrixProduct::<u64, 2>::new(), vec![[[1, 1], [1, 0]]; 10]);
+        assert_eq!(fib.prod(..)[0][1], 55);
+        assert_eq!(fib.prod(3..3), [[1, 0], [0, 1]]);
+
+        let seg = SegmentTree::from_vec(
+            MinWithIndex::new(),
+            v.iter().enumerate().map(|(i, &x)| (x, i)).collect(),
+        );
+        assert_eq!(seg.prod(..), (1, 1));
+        assert_eq!(seg.prod(2..), (1, 3));
+        assert_eq!(seg.prod(4..8), (2, 6));
+
+        let seg = SegmentTree::from_vec(
+            MaxSubarraySum::new(),
+            [2, -5, 3, -1, 4, -10, 1]
+                .into_iter()
+                .map(MaxSubarraySum::single)
+                .collect(),
+        );
+        assert_eq!(seg.prod(..).unwrap().3, 6);
+        assert_eq!(seg.prod(5..).unwrap().3, 1);
+        assert_eq!(seg.prod(1..2).unwrap().3, -5);
+        assert_eq!(seg.prod(3..3), None);
+    }
+
+    #[test]
+    fn lazy_monoids() {
+        let mut seg =
+            LazySegmentTree::from_vec(RangeAddRangeSum::new(), (0..10).map(|i| (i, 1)).collect());
+        seg.apply(2..7, &10);
+        assert_eq!(seg.prod(5..9), (46, 4));
+
+        let mut seg = LazySegmentTree::from_vec(RangeAssignRangeMin::new(), (0..10).collect());
+        seg.apply(..4, &Some(7));
+        assert_eq!(seg.prod(2..6), 4);
+        assert_eq!(seg.prod(..4), 7);
+
+        let mut seg = LazySegmentTree::from_vec(
+            RangeAffineRangeSum::new(),
+            (0..10)
+                .map(|i| (ModInt::<998244353>::new(i), ModInt::new(1)))
+                .collect(),
+        );
+        seg.apply(3..8, &(ModInt::new(2), ModInt::new(1)));
+        seg.apply(..5, &(ModInt::new(3), ModInt::new(0)));
+        assert_eq!(seg.prod(2..6).0, ModInt::new(65));
+
+        let mut seg =
+            LazySegmentTree::from_vec(RangeAddRangeMin::new(), (0..10).map(Some).collect());
+        seg.apply(..5, &10);
+        assert_eq!(seg.prod(3..8), Some(5));
+        assert_eq!(seg.prod(..5), Some(10));
+        assert_eq!(seg.prod(4..4), None);
+
+        let mut seg = LazySegmentTree::from_vec(RangeAddRangeMin::new(), vec![Some(i64::MAX); 3]);
+        seg.apply(1.., &-1);
+        assert_eq!(seg.prod(..), Some(i64::MAX - 1));
+        assert_eq!(seg.prod(..1), Some(i64::MAX));
+    }
+
+    #[test]
+    fn master_managers() {
+        fn build<M: MasterManager>(v: impl IntoIterator<Item = M::T>) -> MasterTree<M> {
+            let mut mt = MasterTree::new();
+            for (i, x) in v.into_iter().enumerate() {
+                mt.insert(i, x);
+            }
+            mt
+        }
+
+        let mut mt = build::<RangeAddRangeSum<i64>>(0..10);
+        mt.apply(2..7, &10);
+        assert_eq!(mt.prod(5..9), 46);
+        mt.reverse();
+        assert_eq!(mt.prod(5..9), 40);
+
+        let mut mt = build::<RangeAssignRangeMin<i32>>(0..10);
+        mt.apply(..4, &7);
+        assert_eq!(mt.prod(2..6), 4);
+        assert_eq!(mt.prod(..4), 7);
+
+        let mut mt = build::<RangeAffineRangeSum<i64>>(0..10);
+        mt.apply(3..8, &(2, 1));
+        mt.apply(..5, &(3, 0));
+        assert_eq!(mt.prod(2..6), 65);
+
+        let mut mt = build::<RangeAddRangeMin<i32>>(0..10);
+        mt.apply(..5, &10);
+        assert_eq!(mt.prod(3..8), 5);
+        assert_eq!(mt.prod(..5), 10);
+
+        let mut mt = build::<RangeAddRangeMin<i32>>([i32::MAX; 3]);
+        mt.apply(1.., &-1);
+        assert_eq!(mt.prod(..), i32::MAX - 1);
+        assert_eq!(mt.prod(..1), i32::MAX);
+    }
+}
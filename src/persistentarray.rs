@@ -246,10 +246,8 @@ impl<T: std::fmt::Debug> std::fmt::Debug for PersistentArray<T> {
 
 impl<T> Extend<T> for PersistentArray<T> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        let mut len = self.len();
-        for v in iter {
+        for (len, v) in (self.len()..).zip(iter) {
             self.push_with_len(len, v);
-            len += 1;
         }
     }
 }
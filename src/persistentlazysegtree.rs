@@ -0,0 +1,313 @@
+use super::{lazysegtree::LazyMonoid, util::to_range};
+use std::{num::NonZero, rc::Rc};
+
+enum RawPersistentLazySegmentTree<M: LazyMonoid> {
+    Value(M::T),
+    Relay(NonZero<u8>, M::T, M::F, (Rc<Self>, Rc<Self>)),
+}
+use RawPersistentLazySegmentTree::*;
+
+impl<M: LazyMonoid<F: Clone>> RawPersistentLazySegmentTree<M> {
+    fn val(&self) -> &M::T {
+        match self {
+            Value(v) => v,
+            Relay(_, v, _, _) => v,
+        }
+    }
+
+    fn apply_all(&mut self, f: &M::F, monoid: &M) {
+        match self {
+            Value(v) => *v = monoid.map(f, v),
+            Relay(_, v, g, _) => {
+                *v = monoid.map(f, v);
+                *g = monoid.comp(f, g);
+            }
+        }
+    }
+
+    fn push(&mut self, monoid: &M) {
+        if let Relay(_, _, f, (l, r)) = self {
+            let f = std::mem::replace(f, monoid.id());
+            Rc::make_mut(l).apply_all(&f, monoid);
+            Rc::make_mut(r).apply_all(&f, monoid);
+        }
+    }
+
+    fn set(&mut self, index: usize, item: M::T, monoid: &M) {
+        self.push(monoid);
+        match self {
+            Value(v) => *v = item,
+            Relay(s, x, _, (l, r)) => {
+                let g = 1 << (s.get() - 1);
+                if index < g {
+                    Rc::make_mut(l).set(index, item, monoid);
+                } else {
+                    Rc::make_mut(r).set(index - g, item, monoid);
+                }
+                *x = monoid.op(l.val(), r.val());
+            }
+        }
+    }
+
+    fn get(&self, mut index: usize, monoid: &M) -> M::T {
+        let mut node = self;
+        let mut f = monoid.id();
+        loop {
+            match node {
+                Value(v) => return monoid.map(&f, v),
+                Relay(s, _, g, (l, r)) => {
+                    f = monoid.comp(&f, g);
+                    let h = 1 << (s.get() - 1);
+                    if index < h {
+                        node = l;
+                    } else {
+                        index -= h;
+                        node = r;
+                    }
+                }
+            }
+        }
+    }
+
+    /// 長さ`len`のこのノードの`left..right`の総積を求める
+    fn prod(&self, len: usize, left: usize, right: usize, monoid: &M) -> M::T {
+        if left == 0 && right == len {
+            return self.val().clone();
+        }
+        let Relay(s, _, f, (l, r)) = self else {
+            unreachable!()
+        };
+        let g = 1 << (s.get() - 1);
+        let v = if right <= g {
+            l.prod(g, left, right, monoid)
+        } else if g <= left {
+            r.prod(len - g, left - g, right - g, monoid)
+        } else {
+            monoid.op(
+                &l.prod(g, left, g, monoid),
+                &r.prod(len - g, 0, right - g, monoid),
+            )
+        };
+        monoid.map(f, &v)
+    }
+
+    /// 長さ`len`のこのノードの`left..right`に作用素`f`を適用する
+    fn apply(&mut self, len: usize, left: usize, right: usize, f: &M::F, monoid: &M) {
+        if left == 0 && right == len {
+            self.apply_all(f, monoid);
+            return;
+        }
+        self.push(monoid);
+        let Relay(s, x, _, (l, r)) = self else {
+            unreachable!()
+        };
+        let g = 1 << (s.get() - 1);
+        if left < g {
+            Rc::make_mut(l).apply(g, left, right.min(g), f, monoid);
+        }
+        if g < right {
+            Rc::make_mut(r).apply(len - g, left.max(g) - g, right - g, f, monoid);
+        }
+        *x = monoid.op(l.val(), r.val());
+    }
+}
+
+impl<M: LazyMonoid<F: Clone>> Clone for RawPersistentLazySegmentTree<M> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Value(arg0) => Self::Value(arg0.clone()),
+            Self::Relay(arg0, arg1, arg2, arg3) => {
+                Self::Relay(*arg0, arg1.clone(), arg2.clone(), arg3.clone())
+            }
+        }
+    }
+}
+
+/// 永続遅延伝搬セグメントツリー
+///
+/// `clone`は*O*(1)で, 各操作は元の版と構造を共有した新しい版を作る
+#[derive(Clone)]
+pub struct PersistentLazySegmentTree<M: LazyMonoid<F: Clone>>(
+    Option<Rc<RawPersistentLazySegmentTree<M>>>,
+    usize,
+    M,
+);
+
+impl<M: LazyMonoid<F: Clone>> PersistentLazySegmentTree<M> {
+    /// 列から永続遅延伝搬セグメントツリーを作る
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*N*)
+    #[must_use]
+    pub fn new(monoid: M, intoiter: impl IntoIterator<Item = M::T>) -> Self {
+        let mut r: Vec<Option<RawPersistentLazySegmentTree<M>>> = vec![];
+        let mut len = 0;
+        'a: for v in intoiter {
+            len += 1;
+            let mut v = Value(v);
+            for (i, r) in r.iter_mut().enumerate() {
+                if let Some(r) = std::mem::take(r) {
+                    v = Relay(
+                        NonZero::new(i as u8 + 1).unwrap(),
+                        monoid.op(r.val(), v.val()),
+                        monoid.id(),
+                        (Rc::new(r), Rc::new(v)),
+                    );
+                } else {
+                    *r = Some(v);
+                    continue 'a;
+                }
+            }
+            r.push(Some(v));
+        }
+        let mut t: Option<Rc<RawPersistentLazySegmentTree<M>>> = None;
+        for (i, r) in r.into_iter().enumerate() {
+            let Some(r) = r else {
+                continue;
+            };
+            if let Some(s) = t {
+                t = Some(Rc::new(Relay(
+                    NonZero::new(i as u8 + 1).unwrap(),
+                    monoid.op(r.val(), s.val()),
+                    monoid.id(),
+                    (Rc::new(r), s),
+                )));
+            } else {
+                t = Some(Rc::new(r));
+            }
+        }
+        Self(t, len, monoid)
+    }
+
+    /// 列の長さを返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.1
+    }
+
+    /// 列が空かどうか判定する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.1 == 0
+    }
+
+    /// 値を設定する
+    ///
+    /// # Constraints
+    ///
+    /// - `index < self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn set(&mut self, index: usize, item: M::T) {
+        debug_assert!(index < self.len());
+        Rc::make_mut(self.0.as_mut().unwrap()).set(index, item, &self.2);
+    }
+
+    /// 値を取得する
+    ///
+    /// # Constraints
+    ///
+    /// - `index < self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn get(&self, index: usize) -> M::T {
+        debug_assert!(index < self.len());
+        self.0.as_ref().unwrap().get(index, &self.2)
+    }
+
+    /// 区間の総積を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn prod(&self, range: impl std::ops::RangeBounds<usize>) -> M::T {
+        let (left, right) = to_range(range, self.len());
+        if left == right {
+            return self.2.e();
+        }
+        self.0
+            .as_ref()
+            .unwrap()
+            .prod(self.len(), left, right, &self.2)
+    }
+
+    /// 区間に作用素`f`を適用する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn apply(&mut self, range: impl std::ops::RangeBounds<usize>, f: &M::F) {
+        let (left, right) = to_range(range, self.len());
+        if left == right {
+            return;
+        }
+        let len = self.len();
+        Rc::make_mut(self.0.as_mut().unwrap()).apply(len, left, right, f, &self.2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monoids::RangeAffineRangeSum;
+
+    #[test]
+    fn affine_sum() {
+        let mut versions = vec![PersistentLazySegmentTree::new(
+            RangeAffineRangeSum::new(),
+            (0..13).map(|i| (i, 1)),
+        )];
+        let mut arrays = vec![(0..13).collect::<Vec<i64>>()];
+        for (from, range, f) in [
+            (0, 2..9, (2, 1)),
+            (1, 0..5, (1, -3)),
+            (0, 4..13, (3, 0)),
+            (2, 1..12, (-1, 2)),
+            (1, 8..9, (0, 7)),
+        ] {
+            let mut seg = versions[from].clone();
+            seg.apply(range.clone(), &f);
+            versions.push(seg);
+            let mut v = arrays[from].clone();
+            for x in &mut v[range] {
+                *x = f.0 * *x + f.1;
+            }
+            arrays.push(v);
+        }
+        versions[4].set(6, (100, 1));
+        arrays[4][6] = 100;
+        for (seg, v) in versions.iter().zip(&arrays) {
+            for l in 0..=13 {
+                for r in l..=13 {
+                    assert_eq!(seg.prod(l..r), (v[l..r].iter().sum(), (r - l) as i64));
+                }
+            }
+            for (i, &x) in v.iter().enumerate() {
+                assert_eq!(seg.get(i), (x, 1));
+            }
+        }
+    }
+}
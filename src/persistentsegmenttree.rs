@@ -1,4 +1,8 @@
-use super::segmenttree::Monoid;
+use super::{
+    binaryindexedtree::AbelianGroup,
+    segmenttree::{Monoid, SegmentTree},
+    util::to_range,
+};
 use std::{num::NonZero, rc::Rc};
 
 enum RawPersistentSegmentTree<M: Monoid> {
@@ -71,6 +75,138 @@ impl<M: Monoid> RawPersistentSegmentTree<M> {
         }
         monoid.op(node.val(), &val)
     }
+    fn level(&self) -> u8 {
+        match self {
+            Value(_) => 0,
+            Relay(n, _, _) => n.get(),
+        }
+    }
+
+    fn push(this: &mut Rc<Self>, len: usize, item: Self, monoid: &M) {
+        if len.is_power_of_two() {
+            *this = Rc::new(Relay(
+                NonZero::new(this.level() + 1).unwrap(),
+                monoid.op(this.val(), item.val()),
+                (this.clone(), Rc::new(item)),
+            ));
+            return;
+        }
+        let Relay(s, x, (l, r)) = Rc::make_mut(this) else {
+            unreachable!()
+        };
+        let g = 1 << (s.get() - 1);
+        Self::push(r, len - g, item, monoid);
+        *x = monoid.op(l.val(), r.val());
+    }
+
+    fn pop(this: &mut Rc<Self>, monoid: &M) -> M::T {
+        let Relay(_, _, (l, r)) = this.as_ref() else {
+            unreachable!()
+        };
+        if let Value(v) = r.as_ref() {
+            let v = v.clone();
+            *this = l.clone();
+            return v;
+        }
+        let Relay(_, x, (l, r)) = Rc::make_mut(this) else {
+            unreachable!()
+        };
+        let v = Self::pop(r, monoid);
+        *x = monoid.op(l.val(), r.val());
+        v
+    }
+
+    /// `left`から右へ`acc`に積を取っていき, `f`が`false`になる位置を返す
+    fn max_right(
+        &self,
+        left: usize,
+        acc: &mut M::T,
+        f: &impl Fn(&M::T) -> bool,
+        monoid: &M,
+    ) -> Option<usize> {
+        if left == 0 {
+            let x = monoid.op(acc, self.val());
+            if f(&x) {
+                *acc = x;
+                return None;
+            }
+        }
+        match self {
+            Value(_) => Some(0),
+            Relay(s, _, (l, r)) => {
+                let g = 1 << (s.get() - 1);
+                if left < g {
+                    if let Some(p) = l.max_right(left, acc, f, monoid) {
+                        return Some(p);
+                    }
+                }
+                r.max_right(left.max(g) - g, acc, f, monoid).map(|p| p + g)
+            }
+        }
+    }
+
+    /// 長さ`len`のこのノードについて, `right`から左へ`acc`に積を取っていき, `f`が`false`になる位置の右端を返す
+    fn min_left(
+        &self,
+        len: usize,
+        right: usize,
+        acc: &mut M::T,
+        f: &impl Fn(&M::T) -> bool,
+        monoid: &M,
+    ) -> Option<usize> {
+        if right == len {
+            let x = monoid.op(self.val(), acc);
+            if f(&x) {
+                *acc = x;
+                return None;
+            }
+        }
+        match self {
+            Value(_) => Some(1),
+            Relay(s, _, (l, r)) => {
+                let g = 1 << (s.get() - 1);
+                if g < right {
+                    if let Some(p) = r.min_left(len - g, right - g, acc, f, monoid) {
+                        return Some(p + g);
+                    }
+                }
+                l.min_left(g, right.min(g), acc, f, monoid)
+            }
+        }
+    }
+}
+
+impl<M: AbelianGroup> RawPersistentSegmentTree<M> {
+    /// 2つのノードの差分について, `left`から右へ`acc`に積を取っていき, `f`が`false`になる位置を返す
+    fn max_right_between(
+        old: &Self,
+        new: &Self,
+        left: usize,
+        acc: &mut M::T,
+        f: &impl Fn(&M::T) -> bool,
+        monoid: &M,
+    ) -> Option<usize> {
+        if left == 0 {
+            let x = monoid.op(acc, &monoid.opinv(new.val(), old.val()));
+            if f(&x) {
+                *acc = x;
+                return None;
+            }
+        }
+        match (old, new) {
+            (Value(_), Value(_)) => Some(0),
+            (Relay(s, _, (ol, or)), Relay(_, _, (nl, nr))) => {
+                let g = 1 << (s.get() - 1);
+                if left < g {
+                    if let Some(p) = Self::max_right_between(ol, nl, left, acc, f, monoid) {
+                        return Some(p);
+                    }
+                }
+                Self::max_right_between(or, nr, left.max(g) - g, acc, f, monoid).map(|p| p + g)
+            }
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl<M: Monoid> Clone for RawPersistentSegmentTree<M> {
@@ -258,12 +394,17 @@ impl<M: Monoid> PersistentSegmentTree<M> {
             }
         }
     }
-}
-
-impl<M: Monoid> std::ops::Index<usize> for PersistentSegmentTree<M> {
-    type Output = M::T;
-
-    fn index(&self, mut index: usize) -> &M::T {
+    /// 値を取得する
+    ///
+    /// # Constraints
+    ///
+    /// - `index < self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn get(&self, mut index: usize) -> &M::T {
         let Some(node) = &self.0 else {
             panic!("PersistentSegmentTree is empty.")
         };
@@ -286,6 +427,206 @@ impl<M: Monoid> std::ops::Index<usize> for PersistentSegmentTree<M> {
             }
         }
     }
+
+    /// 末尾に要素を追加する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn push(&mut self, item: M::T) {
+        let len = self.len();
+        match &mut self.0 {
+            Some(node) => RawPersistentSegmentTree::push(node, len, Value(item), &self.1),
+            None => self.0 = Some(Rc::new(Value(item))),
+        }
+    }
+
+    /// 末尾の要素を削除し, その要素を返す
+    /// 空だった場合は`None`を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn pop(&mut self) -> Option<M::T> {
+        let node = self.0.as_mut()?;
+        if let Value(v) = node.as_ref() {
+            let v = v.clone();
+            self.0 = None;
+            return Some(v);
+        }
+        Some(RawPersistentSegmentTree::pop(node, &self.1))
+    }
+
+    /// 要素を先頭から順に返すイテレータを作る
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*N*) (全体)
+    pub fn iter(&self) -> impl Iterator<Item = &M::T> + use<'_, M> {
+        let mut stack: Vec<&RawPersistentSegmentTree<M>> =
+            self.0.iter().map(|v| v.as_ref()).collect();
+        std::iter::from_fn(move || {
+            let mut node = stack.pop()?;
+            loop {
+                match node {
+                    Value(v) => return Some(v),
+                    Relay(_, _, (l, r)) => {
+                        stack.push(r);
+                        node = l;
+                    }
+                }
+            }
+        })
+    }
+
+    /// `f(self.prod(left..x))`が`true`となる最大の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(self.prod(left..x))`かつ (`x == self.len()`または`!f(self.prod(left..=x))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `left <= self.len()`
+    /// - `f(monoid.e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn max_right(&self, left: usize, f: impl Fn(&M::T) -> bool) -> usize {
+        let len = self.len();
+        debug_assert!(left <= len);
+        let Some(node) = self.0.as_ref().filter(|_| left < len) else {
+            return len;
+        };
+        let mut acc = self.1.e();
+        node.max_right(left, &mut acc, &f, &self.1).unwrap_or(len)
+    }
+
+    /// `f(self.prod(x..right))`が`true`となる最小の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(self.prod(x..right))`かつ (`x == 0`または`!f(self.prod(x - 1..right))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `right <= self.len()`
+    /// - `f(monoid.e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn min_left(&self, right: usize, f: impl Fn(&M::T) -> bool) -> usize {
+        let len = self.len();
+        debug_assert!(right <= len);
+        let Some(node) = self.0.as_ref().filter(|_| right > 0) else {
+            return 0;
+        };
+        let mut acc = self.1.e();
+        node.min_left(len, right, &mut acc, &f, &self.1)
+            .unwrap_or(0)
+    }
+}
+
+impl<M: AbelianGroup> PersistentSegmentTree<M> {
+    /// 2つの版`old`, `new`の差分 (`new`の値から`old`の値を引いたもの) の区間の総積を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `old.len() == new.len()`
+    /// - `range`は`0..old.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn prod_between(old: &Self, new: &Self, range: impl std::ops::RangeBounds<usize>) -> M::T {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        new.1.opinv(&new.prod(range), &old.prod(range))
+    }
+
+    /// 2つの版`old`, `new`の差分について, `f(Self::prod_between(old, new, left..x))`が`true`となる最大の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(Self::prod_between(old, new, left..x))`かつ (`x == old.len()`または`!f(Self::prod_between(old, new, left..=x))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `old.len() == new.len()`
+    /// - `left <= old.len()`
+    /// - `f(monoid.e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn max_right_between(
+        old: &Self,
+        new: &Self,
+        left: usize,
+        f: impl Fn(&M::T) -> bool,
+    ) -> usize {
+        let len = old.len();
+        debug_assert_eq!(len, new.len());
+        debug_assert!(left <= len);
+        let (Some(o), Some(n)) = (&old.0, &new.0) else {
+            return 0;
+        };
+        if left == len {
+            return len;
+        }
+        let mut acc = new.1.e();
+        RawPersistentSegmentTree::max_right_between(o, n, left, &mut acc, &f, &new.1).unwrap_or(len)
+    }
+
+    /// 2つの版`old`, `new`の差分を各位置の個数とみなしたとき, `range`内で`k`番目 (0-indexed) の要素の位置を返す.
+    /// 存在しない場合は`None`を返す.
+    ///
+    /// 値ごとの出現回数を持つ永続SegmentTreeを列の各接頭辞について作っておくと, 区間の`k`番目に小さい値を求められる.
+    ///
+    /// # Constraints
+    ///
+    /// - `old.len() == new.len()`
+    /// - `range`は`0..old.len()`に含まれる区間である.
+    /// - 差分の各要素は`monoid.e()`以上である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn kth_between(
+        old: &Self,
+        new: &Self,
+        range: impl std::ops::RangeBounds<usize>,
+        k: M::T,
+    ) -> Option<usize>
+    where
+        M::T: PartialOrd,
+    {
+        let (left, right) = to_range(range, old.len());
+        let p = Self::max_right_between(old, new, left, |x| *x <= k);
+        (p < right).then_some(p)
+    }
+}
+
+impl<M: Monoid> std::ops::Index<usize> for PersistentSegmentTree<M> {
+    type Output = M::T;
+
+    fn index(&self, index: usize) -> &M::T {
+        self.get(index)
+    }
+}
+
+impl<M: Monoid> From<SegmentTree<M>> for PersistentSegmentTree<M> {
+    fn from(value: SegmentTree<M>) -> Self {
+        let (vec, monoid) = value.into_inner();
+        Self::new(monoid, vec)
+    }
+}
+
+impl<M: Monoid> From<PersistentSegmentTree<M>> for SegmentTree<M> {
+    fn from(value: PersistentSegmentTree<M>) -> Self {
+        let vec = value.iter().cloned().collect();
+        SegmentTree::from_vec(value.1, vec)
+    }
 }
 
 impl<M: Monoid> std::fmt::Debug for PersistentSegmentTree<M>
@@ -358,4 +699,104 @@ mod tests {
         assert_eq!(seg2.prod(1..5), 15);
         assert_eq!(seg2.prod(4..10), 39);
     }
+
+    #[test]
+    fn kth_between() {
+        use crate::monoids::Sum;
+
+        let a = [5, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9];
+        let mut versions = vec![PersistentSegmentTree::new(Sum::new(), [0i64; 10])];
+        for &x in &a {
+            let mut seg = versions.last().unwrap().clone();
+            seg.set(x, seg[x] + 1);
+            versions.push(seg);
+        }
+        for l in 0..=a.len() {
+            for r in l..=a.len() {
+                let mut b = a[l..r].to_vec();
+                b.sort();
+                for k in 0..=b.len() {
+                    assert_eq!(
+                        PersistentSegmentTree::kth_between(
+                            &versions[l],
+                            &versions[r],
+                            ..,
+                            k as i64
+                        ),
+                        b.get(k).copied()
+                    );
+                }
+                assert_eq!(
+                    PersistentSegmentTree::kth_between(&versions[l], &versions[r], 3..7, 0),
+                    b.iter().copied().find(|x| (3..7).contains(x))
+                );
+                for lo in 0..=10 {
+                    assert_eq!(
+                        PersistentSegmentTree::kth_between(&versions[l], &versions[r], lo.., 0),
+                        b.iter().copied().find(|&x| x >= lo)
+                    );
+                }
+                assert_eq!(
+                    PersistentSegmentTree::max_right_between(
+                        &versions[l],
+                        &versions[r],
+                        10,
+                        |_| { false }
+                    ),
+                    10
+                );
+                assert_eq!(
+                    PersistentSegmentTree::prod_between(&versions[l], &versions[r], 2..6),
+                    b.iter().filter(|x| (2..6).contains(*x)).count() as i64
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn push_pop_search() {
+        use crate::monoids::Sum;
+
+        let mut v: Vec<i64> = vec![];
+        let mut seg = PersistentSegmentTree::new(Sum::new(), []);
+        let mut versions = vec![];
+        for i in 0..20 {
+            versions.push((seg.clone(), v.clone()));
+            if i % 5 == 4 {
+                assert_eq!(seg.pop(), v.pop());
+            } else {
+                seg.push(i * 3 % 7);
+                v.push(i * 3 % 7);
+            }
+        }
+        versions.push((seg, v));
+        for (seg, v) in &versions {
+            assert_eq!(seg.len(), v.len());
+            assert!(seg.iter().eq(v.iter()));
+            for l in 0..=v.len() {
+                for r in l..=v.len() {
+                    assert_eq!(seg.prod(l..r), v[l..r].iter().sum());
+                }
+                for w in 0..20 {
+                    let r = (l..=v.len())
+                        .take_while(|&r| v[l..r].iter().sum::<i64>() <= w)
+                        .last()
+                        .unwrap();
+                    assert_eq!(seg.max_right(l, |&x| x <= w), r);
+                    let x = (0..=l)
+                        .rev()
+                        .take_while(|&x| v[x..l].iter().sum::<i64>() <= w)
+                        .last()
+                        .unwrap();
+                    assert_eq!(seg.min_left(l, |&x| x <= w), x);
+                }
+            }
+        }
+
+        let (seg, v) = versions.pop().unwrap();
+        let seg = SegmentTree::from(seg);
+        assert_eq!(seg.as_ref(), &v[..]);
+        let seg = PersistentSegmentTree::from(seg);
+        assert!(seg.iter().eq(v.iter()));
+    }
 }
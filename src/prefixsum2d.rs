@@ -0,0 +1,107 @@
+use super::{binaryindexedtree::AbelianGroup, util::to_range};
+
+/// 2次元累積和
+///
+/// 静的な2次元配列の長方形領域の総和を定数時間で計算する
+#[derive(Clone)]
+pub struct PrefixSum2D<G: AbelianGroup>(Box<[G::T]>, usize, usize, G);
+
+impl<G: AbelianGroup> PrefixSum2D<G> {
+    /// `h`行`w`列の2次元配列から2次元累積和を構築する
+    ///
+    /// # Constraints
+    ///
+    /// - `grid`の各行の長さは等しい.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*HW*)
+    #[must_use]
+    pub fn new(group: G, grid: impl Into<Vec<Vec<G::T>>>) -> Self {
+        let grid: Vec<Vec<G::T>> = grid.into();
+        let h = grid.len();
+        let w = grid.first().map_or(0, Vec::len);
+        debug_assert!(grid.iter().all(|row| row.len() == w));
+        let mut sum = vec![group.e(); (h + 1) * (w + 1)];
+        for (i, row) in grid.iter().enumerate() {
+            for (j, v) in row.iter().enumerate() {
+                let x = group.op(&sum[i * (w + 1) + j + 1], &sum[(i + 1) * (w + 1) + j]);
+                sum[(i + 1) * (w + 1) + j + 1] =
+                    group.op(&group.opinv(&x, &sum[i * (w + 1) + j]), v);
+            }
+        }
+        Self(sum.into(), h, w, group)
+    }
+
+    /// 行数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.1
+    }
+
+    /// 列数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.2
+    }
+
+    /// `rows`行目と`cols`列目の範囲の長方形領域の総和を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `rows`は`0..self.height()`に含まれる区間である.
+    /// - `cols`は`0..self.width()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn sum(
+        &self,
+        rows: impl std::ops::RangeBounds<usize>,
+        cols: impl std::ops::RangeBounds<usize>,
+    ) -> G::T {
+        let (top, bottom) = to_range(rows, self.1);
+        let (left, right) = to_range(cols, self.2);
+        let at = |i: usize, j: usize| &self.0[i * (self.2 + 1) + j];
+        let x = self.3.op(at(bottom, right), at(top, left));
+        let y = self.3.op(at(top, right), at(bottom, left));
+        self.3.opinv(&x, &y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monoids::Sum;
+
+    #[test]
+    fn sum() {
+        let grid = (0..6)
+            .map(|i| {
+                (0..9)
+                    .map(|j| (i * 3 + j * 5) % 11 - 5)
+                    .collect::<Vec<i64>>()
+            })
+            .collect::<Vec<_>>();
+        let prefix = PrefixSum2D::new(Sum::new(), grid.clone());
+        for t in 0..=6 {
+            for b in t..=6 {
+                for l in 0..=9 {
+                    for r in l..=9 {
+                        let expected: i64 = grid[t..b].iter().flat_map(|row| &row[l..r]).sum();
+                        assert_eq!(prefix.sum(t..b, l..r), expected);
+                    }
+                }
+            }
+        }
+    }
+}
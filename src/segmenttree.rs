@@ -37,7 +37,7 @@ impl<M: Monoid> SegmentTree<M> {
             let mut offset = 0;
             while len > 1 {
                 for i in (1..len).step_by(2) {
-                    vec.push(monoid.op(&vec[i + offset], &vec[i + offset - 1]));
+                    vec.push(monoid.op(&vec[i + offset - 1], &vec[i + offset]));
                 }
                 offset += len;
                 len >>= 1;
@@ -46,6 +46,13 @@ impl<M: Monoid> SegmentTree<M> {
         Self(vec.into_boxed_slice(), n, monoid)
     }
 
+    /// 列とモノイドに分解する
+    pub(super) fn into_inner(self) -> (Vec<M::T>, M) {
+        let mut vec = self.0.into_vec();
+        vec.truncate(self.1);
+        (vec, self.2)
+    }
+
     /// SegmentTreeの長さを返す.
     ///
     /// # Time complexity
@@ -189,6 +196,7 @@ impl<M: Monoid> SegmentTree<M> {
     /// `f(self.prod(index..x))`だが`!f(self.prod(index..=x))`な最小の`x`を見つけるような二分探索を行う.
     ///
     /// 見つからなかった場合は`self.len()`を返す.
+    /// `self.max_right(index, f)`と同じ.
     ///
     /// # Constraints
     ///
@@ -198,7 +206,26 @@ impl<M: Monoid> SegmentTree<M> {
     ///
     /// - *O*(log *n*)
     #[must_use]
-    pub fn binary_search(&self, mut index: usize, f: impl Fn(&M::T) -> bool) -> usize {
+    pub fn binary_search(&self, index: usize, f: impl Fn(&M::T) -> bool) -> usize {
+        self.max_right(index, f)
+    }
+
+    /// `f(self.prod(left..x))`が`true`となる最大の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(self.prod(left..x))`かつ (`x == self.len()`または`!f(self.prod(left..=x))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `left <= self.len()`
+    /// - `f(monoid.e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    #[must_use]
+    pub fn max_right(&self, left: usize, f: impl Fn(&M::T) -> bool) -> usize {
+        debug_assert!(left <= self.len());
+        let mut index = left;
         let mut sh = 0;
         let mut offset = 0;
         let mut r = self.2.e();
@@ -234,6 +261,55 @@ impl<M: Monoid> SegmentTree<M> {
         }
         index
     }
+
+    /// `f(self.prod(x..right))`が`true`となる最小の`x`を返す.
+    ///
+    /// `f`が単調でない場合は, `f(self.prod(x..right))`かつ (`x == 0`または`!f(self.prod(x - 1..right))`) を満たす`x`のいずれかを返す.
+    ///
+    /// # Constraints
+    ///
+    /// - `right <= self.len()`
+    /// - `f(monoid.e())`は`true`である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    #[must_use]
+    pub fn min_left(&self, right: usize, f: impl Fn(&M::T) -> bool) -> usize {
+        debug_assert!(right <= self.len());
+        let mut index = right;
+        let mut sh = 0;
+        let mut offset = 0;
+        let mut r = self.2.e();
+        debug_assert!(f(&r));
+        while index > 0 {
+            while index & 1 == 0 {
+                index >>= 1;
+                offset += self.1 >> sh;
+                sh += 1;
+            }
+            let temp = self.2.op(&self.0[index - 1 + offset], &r);
+            if !f(&temp) {
+                break;
+            }
+            r = temp;
+            index -= 1;
+        }
+        if index == 0 {
+            return 0;
+        }
+        while sh > 0 {
+            sh -= 1;
+            offset -= self.1 >> sh;
+            index <<= 1;
+            let temp = self.2.op(&self.0[index - 1 + offset], &r);
+            if f(&temp) {
+                r = temp;
+                index -= 1;
+            }
+        }
+        index
+    }
 }
 
 impl<M: Monoid + Default> From<Vec<M::T>> for SegmentTree<M> {
@@ -294,4 +370,59 @@ mod tests {
         assert_eq!(seg.binary_search(0, |&v| v < 22), 10);
         assert_eq!(seg.binary_search(1, |&v| v < 22), 10);
     }
+
+    #[test]
+    fn from_vec_non_commutative() {
+        use super::super::util::FnMonoid;
+
+        let items = ["a", "b", "c", "d", "e", "f", "g"].map(String::from);
+        let seg = SegmentTree::from_vec(
+            FnMonoid::new(String::new(), |a: &String, b: &String| a.clone() + b),
+            items.to_vec(),
+        );
+        for l in 0..=items.len() {
+            for r in l..=items.len() {
+                assert_eq!(seg.prod(l..r), items[l..r].concat());
+            }
+        }
+    }
+
+    #[test]
+    fn fn_monoid() {
+        use super::super::util::FnMonoid;
+
+        let mut seg = SegmentTree::from_vec(
+            FnMonoid::new(f64::INFINITY, |a: &f64, b: &f64| a.min(*b)),
+            vec![3.5, 1.25, 4.0, 1.5, 5.0, 9.0],
+        );
+        assert_eq!(seg.prod(..), 1.25);
+        assert_eq!(seg.prod(2..), 1.5);
+        assert_eq!(seg.prod(4..4), f64::INFINITY);
+        seg.set(3, 7.0);
+        assert_eq!(seg.prod(2..), 4.0);
+    }
+
+    #[test]
+    fn max_right_min_left() {
+        use super::super::monoids::{Affine, Sum};
+
+        let v = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        for n in 0..=v.len() {
+            let seg = SegmentTree::from_vec(Sum::new(), v[..n].to_vec());
+            for i in 0..=n {
+                for k in 0..30 {
+                    let r = (i..=n).rev().find(|&r| v[i..r].iter().sum::<i32>() <= k);
+                    assert_eq!(Some(seg.max_right(i, |&s| s <= k)), r);
+                    let l = (0..=i).find(|&l| v[l..i].iter().sum::<i32>() <= k);
+                    assert_eq!(Some(seg.min_left(i, |&s| s <= k)), l);
+                }
+            }
+        }
+
+        // x ↦ 2x + 1 を繰り返し適用して100以下に収まる範囲
+        let seg = SegmentTree::from_vec(Affine::new(), vec![(2, 1); 13]);
+        assert_eq!(seg.max_right(3, |&(a, b)| a + b <= 100), 8);
+        assert_eq!(seg.min_left(12, |&(a, b)| a + b <= 100), 7);
+        assert_eq!(seg.min_left(4, |&(a, b)| a + b <= 100), 0);
+    }
 }
@@ -0,0 +1,492 @@
+use super::segmenttree::Monoid;
+use super::util::{to_range, Associativity, HasMax, HasMin, HasOne, HasZero, Identity, Magma};
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Sub};
+
+/// Segment Tree Beatsで使う作用付きモノイド
+///
+/// 作用素の適用が失敗しうるため`LazyMonoid`とは別のトレイトになっている
+pub trait BeatsMonoid: Monoid {
+    /// 作用素の型
+    type F;
+
+    /// 恒等写像を得る
+    fn id(&self) -> Self::F;
+    /// `f`を作用させた後に`g`を作用させる作用素を得る
+    fn comp(&self, g: &Self::F, f: &Self::F) -> Self::F;
+    /// `x`に作用素`f`を適用した値を計算する.
+    ///
+    /// ノードの情報だけから計算できない場合は`None`を返し, そのときは子ノードに再帰して計算される.
+    /// 長さ1の区間の値に対しては必ず`Some`を返す必要がある.
+    fn try_map(&self, f: &Self::F, x: &Self::T) -> Option<Self::T>;
+}
+
+/// Segment Tree Beats
+///
+/// 作用素の適用が失敗した場合に子へ再帰することで, 区間chminのような`LazySegmentTree`で扱えない区間更新ができる
+pub struct SegmentTreeBeats<M: BeatsMonoid>(Box<[M::T]>, Box<[M::F]>, usize, M);
+
+impl<M: BeatsMonoid> SegmentTreeBeats<M> {
+    /// 全ての要素が`monoid.e()`で初期化された長さ`n`のSegmentTreeBeatsを構築する.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*n*)
+    #[must_use]
+    pub fn new(monoid: M, n: usize) -> Self {
+        let vec = (0..n).map(|_| monoid.e()).collect();
+        Self::from_vec(monoid, vec)
+    }
+
+    /// 配列`vec`からSegmentTreeBeatsを構築する.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*n*)
+    #[must_use]
+    pub fn from_vec(monoid: M, vec: Vec<M::T>) -> Self {
+        let n = vec.len();
+        let size = n.next_power_of_two();
+        let mut data: Vec<M::T> = (0..size).map(|_| monoid.e()).collect();
+        data.extend(vec);
+        data.extend((n..size).map(|_| monoid.e()));
+        for i in (1..size).rev() {
+            data[i] = monoid.op(&data[i * 2], &data[i * 2 + 1]);
+        }
+        Self(
+            data.into_boxed_slice(),
+            (0..size).map(|_| monoid.id()).collect(),
+            n,
+            monoid,
+        )
+    }
+
+    /// 列の長さを返す.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.2
+    }
+
+    /// 列が空かどうか判定する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.2 == 0
+    }
+
+    fn apply_node(&mut self, k: usize, f: &M::F) {
+        if let Some(v) = self.3.try_map(f, &self.0[k]) {
+            self.0[k] = v;
+            if k < self.1.len() {
+                self.1[k] = self.3.comp(f, &self.1[k]);
+            }
+        } else {
+            debug_assert!(k < self.1.len());
+            self.push(k);
+            self.apply_node(k * 2, f);
+            self.apply_node(k * 2 + 1, f);
+            self.pull(k);
+        }
+    }
+
+    fn push(&mut self, k: usize) {
+        let f = std::mem::replace(&mut self.1[k], self.3.id());
+        self.apply_node(k * 2, &f);
+        self.apply_node(k * 2 + 1, &f);
+    }
+
+    fn pull(&mut self, k: usize) {
+        self.0[k] = self.3.op(&self.0[k * 2], &self.0[k * 2 + 1]);
+    }
+
+    /// 指定した位置の値を変更する
+    ///
+    /// # Constraints
+    ///
+    /// - `index < self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn set(&mut self, index: usize, item: M::T) {
+        debug_assert!(index < self.len());
+        let size = self.1.len();
+        for i in (1..=size.trailing_zeros()).rev() {
+            self.push((index + size) >> i);
+        }
+        self.0[index + size] = item;
+        for i in 1..=size.trailing_zeros() {
+            self.pull((index + size) >> i);
+        }
+    }
+
+    /// 指定した位置の値を取得する
+    ///
+    /// # Constraints
+    ///
+    /// - `index < self.len()`
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    #[must_use]
+    pub fn get(&mut self, index: usize) -> &M::T {
+        debug_assert!(index < self.len());
+        let size = self.1.len();
+        for i in (1..=size.trailing_zeros()).rev() {
+            self.push((index + size) >> i);
+        }
+        &self.0[index + size]
+    }
+
+    /// 指定した区間の値の総積を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *n*)
+    pub fn prod(&mut self, range: impl std::ops::RangeBounds<usize>) -> M::T {
+        fn internal<M: BeatsMonoid>(
+            seg: &mut SegmentTreeBeats<M>,
+            k: usize,
+            (nl, nr): (usize, usize),
+            (l, r): (usize, usize),
+        ) -> M::T {
+            if r <= nl || nr <= l {
+                return seg.3.e();
+            }
+            if l <= nl && nr <= r {
+                return seg.0[k].clone();
+            }
+            seg.push(k);
+            let mid = (nl + nr) / 2;
+            let lv = internal(seg, k * 2, (nl, mid), (l, r));
+            let rv = internal(seg, k * 2 + 1, (mid, nr), (l, r));
+            seg.3.op(&lv, &rv)
+        }
+
+        let (left, right) = to_range(range, self.len());
+        if left == right {
+            return self.3.e();
+        }
+        let size = self.1.len();
+        internal(self, 1, (0, size), (left, right))
+    }
+
+    /// 指定した区間に作用素`f`を適用する
+    ///
+    /// # Constraints
+    ///
+    /// - `range`は`0..self.len()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - ならし*O*(log² *n*) (区間chmin・chmax・加算の場合)
+    pub fn apply(&mut self, range: impl std::ops::RangeBounds<usize>, f: &M::F) {
+        fn internal<M: BeatsMonoid>(
+            seg: &mut SegmentTreeBeats<M>,
+            k: usize,
+            (nl, nr): (usize, usize),
+            (l, r): (usize, usize),
+            f: &M::F,
+        ) {
+            if r <= nl || nr <= l {
+                return;
+            }
+            if l <= nl && nr <= r {
+                seg.apply_node(k, f);
+                return;
+            }
+            seg.push(k);
+            let mid = (nl + nr) / 2;
+            internal(seg, k * 2, (nl, mid), (l, r), f);
+            internal(seg, k * 2 + 1, (mid, nr), (l, r), f);
+            seg.pull(k);
+        }
+
+        let (left, right) = to_range(range, self.len());
+        if left == right {
+            return;
+        }
+        let size = self.1.len();
+        internal(self, 1, (0, size), (left, right), f);
+    }
+}
+
+/// 区間chmin・区間chmax・区間加算と, 区間和・区間最小値・区間最大値を扱う作用付きモノイド
+///
+/// 作用素`(a, lo, hi)`は`x ↦ min(max(x + a, lo), hi)`を表す.
+pub struct ChminChmaxAdd<T>(PhantomData<fn() -> T>);
+
+/// `ChminChmaxAdd`の値. 区間の情報を持つ
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct BeatsInfo<T> {
+    /// 区間和
+    pub sum: T,
+    /// 最大値
+    pub max: T,
+    /// 2番目に大きい値 (存在しなければ`T::min_value()`)
+    pub max2: T,
+    /// 最大値の個数
+    pub max_cnt: T,
+    /// 最小値
+    pub min: T,
+    /// 2番目に小さい値 (存在しなければ`T::max_value()`)
+    pub min2: T,
+    /// 最小値の個数
+    pub min_cnt: T,
+    /// 区間の長さ
+    pub len: T,
+}
+
+impl<T: Copy + HasZero + HasOne + HasMin + HasMax> BeatsInfo<T> {
+    /// 長さ1の列`[x]`に対応する値を返す
+    #[must_use]
+    pub fn new(x: T) -> Self {
+        Self {
+            sum: x,
+            max: x,
+            max2: T::min_value(),
+            max_cnt: T::one(),
+            min: x,
+            min2: T::max_value(),
+            min_cnt: T::one(),
+            len: T::one(),
+        }
+    }
+}
+
+impl<T> ChminChmaxAdd<T> {
+    /// 新しく作る
+    #[must_use]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+impl<T: HasZero + HasMin> ChminChmaxAdd<T> {
+    /// `x ↦ min(x, v)`を表す作用素を返す
+    #[must_use]
+    pub fn chmin(v: T) -> (T, T, T) {
+        (T::zero(), T::min_value(), v)
+    }
+}
+impl<T: HasZero + HasMax> ChminChmaxAdd<T> {
+    /// `x ↦ max(x, v)`を表す作用素を返す
+    #[must_use]
+    pub fn chmax(v: T) -> (T, T, T) {
+        (T::zero(), v, T::max_value())
+    }
+}
+impl<T: HasMin + HasMax> ChminChmaxAdd<T> {
+    /// `x ↦ x + v`を表す作用素を返す
+    #[must_use]
+    pub fn add(v: T) -> (T, T, T) {
+        (v, T::min_value(), T::max_value())
+    }
+}
+impl<T> Clone for ChminChmaxAdd<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for ChminChmaxAdd<T> {}
+impl<T> Default for ChminChmaxAdd<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `ChminChmaxAdd`で値に要求するトレイト
+pub trait BeatsNum:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + HasZero
+    + HasOne
+    + HasMin
+    + HasMax
+{
+}
+impl<T> BeatsNum for T where
+    T: Copy
+        + Ord
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + HasZero
+        + HasOne
+        + HasMin
+        + HasMax
+{
+}
+
+/// 番兵の値を保ったまま加算する
+fn shift<T: BeatsNum>(x: T, a: T) -> T {
+    if x == T::min_value() || x == T::max_value() {
+        x
+    } else {
+        x + a
+    }
+}
+
+impl<T: BeatsNum> Magma for ChminChmaxAdd<T> {
+    type T = BeatsInfo<T>;
+    fn op(&self, a: &BeatsInfo<T>, b: &BeatsInfo<T>) -> BeatsInfo<T> {
+        use std::cmp::Ordering::*;
+        if a.len == T::zero() {
+            return *b;
+        }
+        if b.len == T::zero() {
+            return *a;
+        }
+        let (max, max2, max_cnt) = match a.max.cmp(&b.max) {
+            Less => (b.max, a.max.max(b.max2), b.max_cnt),
+            Equal => (a.max, a.max2.max(b.max2), a.max_cnt + b.max_cnt),
+            Greater => (a.max, a.max2.max(b.max), a.max_cnt),
+        };
+        let (min, min2, min_cnt) = match a.min.cmp(&b.min) {
+            Less => (a.min, a.min2.min(b.min), a.min_cnt),
+            Equal => (a.min, a.min2.min(b.min2), a.min_cnt + b.min_cnt),
+            Greater => (b.min, a.min.min(b.min2), b.min_cnt),
+        };
+        BeatsInfo {
+            sum: a.sum + b.sum,
+            max,
+            max2,
+            max_cnt,
+            min,
+            min2,
+            min_cnt,
+            len: a.len + b.len,
+        }
+    }
+}
+impl<T: BeatsNum> Identity for ChminChmaxAdd<T> {
+    fn e(&self) -> BeatsInfo<T> {
+        BeatsInfo {
+            sum: T::zero(),
+            max: T::min_value(),
+            max2: T::min_value(),
+            max_cnt: T::zero(),
+            min: T::max_value(),
+            min2: T::max_value(),
+            min_cnt: T::zero(),
+            len: T::zero(),
+        }
+    }
+}
+impl<T: BeatsNum> Associativity for ChminChmaxAdd<T> {}
+
+impl<T: BeatsNum> BeatsMonoid for ChminChmaxAdd<T> {
+    type F = (T, T, T);
+    fn id(&self) -> (T, T, T) {
+        Self::add(T::zero())
+    }
+    fn comp(&self, &(ga, gl, gh): &(T, T, T), &(fa, fl, fh): &(T, T, T)) -> (T, T, T) {
+        (
+            fa + ga,
+            shift(fl, ga).max(gl).min(gh),
+            shift(fh, ga).max(gl).min(gh),
+        )
+    }
+    fn try_map(&self, &(a, lo, hi): &(T, T, T), x: &BeatsInfo<T>) -> Option<BeatsInfo<T>> {
+        if x.len == T::zero() {
+            return Some(*x);
+        }
+        let mut y = *x;
+        y.sum = y.sum + a * y.len;
+        y.max = y.max + a;
+        y.max2 = shift(y.max2, a);
+        y.min = y.min + a;
+        y.min2 = shift(y.min2, a);
+        if lo > y.min {
+            if lo >= y.min2 {
+                return None;
+            }
+            y.sum = y.sum + (lo - y.min) * y.min_cnt;
+            if y.min == y.max {
+                y.max = lo;
+            } else if y.min == y.max2 {
+                y.max2 = lo;
+            }
+            y.min = lo;
+        }
+        if hi < y.max {
+            if hi <= y.max2 {
+                return None;
+            }
+            y.sum = y.sum - (y.max - hi) * y.max_cnt;
+            if y.max == y.min {
+                y.min = hi;
+            } else if y.max == y.min2 {
+                y.min2 = hi;
+            }
+            y.max = hi;
+        }
+        Some(y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chmin_chmax_add() {
+        type M = ChminChmaxAdd<i64>;
+        let mut v: Vec<i64> = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4];
+        let mut seg =
+            SegmentTreeBeats::from_vec(M::new(), v.iter().map(|&x| BeatsInfo::new(x)).collect());
+        let mut seed = 0x2545f4914f6cdd1du64;
+        let mut rand = |m: u64| {
+            seed ^= seed << 7;
+            seed ^= seed >> 9;
+            seed % m
+        };
+        for _ in 0..1000 {
+            let l = rand(v.len() as u64 + 1) as usize;
+            let r = rand(v.len() as u64 + 1) as usize;
+            let (l, r) = (l.min(r), l.max(r));
+            let x = rand(21) as i64 - 10;
+            match rand(5) {
+                0 => {
+                    seg.apply(l..r, &M::chmin(x));
+                    v[l..r].iter_mut().for_each(|v| *v = (*v).min(x));
+                }
+                1 => {
+                    seg.apply(l..r, &M::chmax(x));
+                    v[l..r].iter_mut().for_each(|v| *v = (*v).max(x));
+                }
+                2 => {
+                    seg.apply(l..r, &M::add(x));
+                    v[l..r].iter_mut().for_each(|v| *v += x);
+                }
+                3 if l < v.len() => {
+                    seg.set(l, BeatsInfo::new(x));
+                    v[l] = x;
+                }
+                _ => {
+                    let p = seg.prod(l..r);
+                    assert_eq!(p.sum, v[l..r].iter().sum::<i64>());
+                    assert_eq!(p.max, v[l..r].iter().copied().max().unwrap_or(i64::MIN));
+                    assert_eq!(p.min, v[l..r].iter().copied().min().unwrap_or(i64::MAX));
+                    if l < v.len() {
+                        assert_eq!(seg.get(l).sum, v[l]);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use super::{mastertree::MasterTree, monoids::Ordered};
+
+/// 順序統計量を扱える多重集合
+///
+/// 値の追加・削除に加えて, 何番目に小さい値か, ある値未満の値がいくつあるかを求められる
+///
+/// 内部ではコピーオンライトのMasterTreeを使うため`T: Clone`が必要になる.
+/// `clone`はノードを共有して*O*(1)で行われ, その後に共有されたノードを書き換えるときに値が複製される.
+pub struct SortedMultiset<T: Ord + Clone>(MasterTree<Ordered<T>>);
+
+impl<T: Ord + Clone> SortedMultiset<T> {
+    /// 空のSortedMultisetを作る
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn new() -> Self {
+        Self(MasterTree::new())
+    }
+
+    /// 要素数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// 空かどうか調べる
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 値を1つ追加する
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn insert(&mut self, item: T) {
+        self.0.insert_sorted(item);
+    }
+
+    /// `item`と等しい値を1つ削除する. 削除できたかどうかを返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    pub fn remove_one(&mut self, item: &T) -> bool {
+        self.0.remove_sorted(item).is_some()
+    }
+
+    /// `item`と等しい値の個数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn count(&self, item: &T) -> usize {
+        self.0.upper_bound(item) - self.0.lower_bound(item)
+    }
+
+    /// `item`と等しい値が含まれるか調べる
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn contains(&self, item: &T) -> bool {
+        self.nth(self.0.lower_bound(item)) == Some(item)
+    }
+
+    /// `item`未満の値の個数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn rank(&self, item: &T) -> usize {
+        self.0.rank(item)
+    }
+
+    /// `k`番目(0-indexed)に小さい値を返す. `k >= self.len()`の場合は`None`を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn nth(&self, k: usize) -> Option<&T> {
+        (k < self.len()).then(|| &self.0[k])
+    }
+
+    /// `item`以上の最小の値を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn lower_bound(&self, item: &T) -> Option<&T> {
+        self.nth(self.0.lower_bound(item))
+    }
+
+    /// `item`より大きい最小の値を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn upper_bound(&self, item: &T) -> Option<&T> {
+        self.nth(self.0.upper_bound(item))
+    }
+
+    /// `item`未満の最大の値を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn prev(&self, item: &T) -> Option<&T> {
+        self.0.lower_bound(item).checked_sub(1).map(|k| &self.0[k])
+    }
+
+    /// `item`以下の最大の値を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn prev_or_equal(&self, item: &T) -> Option<&T> {
+        self.0.upper_bound(item).checked_sub(1).map(|k| &self.0[k])
+    }
+
+    /// `item`より大きい最小の値を返す. `upper_bound`と同じ
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn next(&self, item: &T) -> Option<&T> {
+        self.upper_bound(item)
+    }
+
+    /// 最小の値を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn first(&self) -> Option<&T> {
+        self.nth(0)
+    }
+
+    /// 最大の値を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn last(&self) -> Option<&T> {
+        self.len().checked_sub(1).map(|k| &self.0[k])
+    }
+
+    /// `range`に含まれる値の個数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(log *N*)
+    #[must_use]
+    pub fn count_range(&self, range: impl std::ops::RangeBounds<T>) -> usize {
+        let left = match range.start_bound() {
+            std::ops::Bound::Included(x) => self.0.lower_bound(x),
+            std::ops::Bound::Excluded(x) => self.0.upper_bound(x),
+            std::ops::Bound::Unbounded => 0,
+        };
+        let right = match range.end_bound() {
+            std::ops::Bound::Included(x) => self.0.upper_bound(x),
+            std::ops::Bound::Excluded(x) => self.0.lower_bound(x),
+            std::ops::Bound::Unbounded => self.len(),
+        };
+        right.saturating_sub(left)
+    }
+
+    /// 値を小さい順に返すイテレータを返す
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + use<'_, T> {
+        self.0.iter()
+    }
+}
+
+impl<T: Ord + Clone> Clone for SortedMultiset<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Ord + Clone> Default for SortedMultiset<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone + std::fmt::Debug> std::fmt::Debug for SortedMultiset<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for SortedMultiset<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec: Vec<T> = iter.into_iter().collect();
+        vec.sort();
+        Self(MasterTree::from_vec(vec))
+    }
+}
+
+impl<T: Ord + Clone> Extend<T> for SortedMultiset<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brute() {
+        let mut set = SortedMultiset::new();
+        let mut v: Vec<i32> = vec![];
+        for i in 0..300i32 {
+            let x = i * 37 % 23 - 11;
+            if i % 3 == 2 {
+                let pos = v.iter().position(|&y| y == x);
+                assert_eq!(set.remove_one(&x), pos.is_some());
+                if let Some(pos) = pos {
+                    v.remove(pos);
+                }
+            } else {
+                set.insert(x);
+                v.push(x);
+                v.sort_unstable();
+            }
+            assert_eq!(set.len(), v.len());
+            for y in -13..13 {
+                assert_eq!(set.count(&y), v.iter().filter(|&&z| z == y).count());
+                assert_eq!(set.contains(&y), v.contains(&y));
+                assert_eq!(set.rank(&y), v.iter().filter(|&&z| z < y).count());
+                assert_eq!(set.lower_bound(&y), v.iter().find(|&&z| z >= y));
+                assert_eq!(set.upper_bound(&y), v.iter().find(|&&z| z > y));
+                assert_eq!(set.next(&y), v.iter().find(|&&z| z > y));
+                assert_eq!(set.prev(&y), v.iter().rev().find(|&&z| z < y));
+                assert_eq!(set.prev_or_equal(&y), v.iter().rev().find(|&&z| z <= y));
+                assert_eq!(
+                    set.count_range(y..y + 5),
+                    v.iter().filter(|&&z| (y..y + 5).contains(&z)).count()
+                );
+                assert_eq!(set.count_range(..=y), v.iter().filter(|&&z| z <= y).count());
+            }
+            for k in 0..=v.len() {
+                assert_eq!(set.nth(k), v.get(k));
+            }
+        }
+        assert_eq!(set.first(), v.first());
+        assert_eq!(set.last(), v.last());
+        assert!(set.iter().eq(v.iter()));
+        let set: SortedMultiset<i32> = v.iter().rev().copied().collect();
+        assert!(set.iter().eq(v.iter()));
+    }
+}
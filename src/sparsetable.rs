@@ -1,4 +1,4 @@
-use super::util::{Associativity, Idempotence, Identity};
+use super::util::{to_range, Associativity, Idempotence, Identity};
 
 /// 冪等性を表すトレイト
 ///
@@ -97,6 +97,116 @@ where
     }
 }
 
+/// 長方形領域の最小値クエリなどを定数時間で処理できるデータ構造
+#[derive(Clone)]
+pub struct SparseTable2D<M: IdempotentMonoid>(Box<[M::T]>, usize, usize, M);
+
+impl<M: IdempotentMonoid> SparseTable2D<M> {
+    /// `h`行`w`列の2次元配列からSparseTable2Dを構築する
+    ///
+    /// # Constraints
+    ///
+    /// - `grid`の各行の長さは等しい.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(*HW* log *H* log *W*)
+    #[must_use]
+    pub fn new(monoid: M, grid: impl Into<Vec<Vec<M::T>>>) -> Self {
+        let grid: Vec<Vec<M::T>> = grid.into();
+        let h = grid.len();
+        let w = grid.first().map_or(0, Vec::len);
+        debug_assert!(grid.iter().all(|row| row.len() == w));
+        let lh = (usize::BITS - h.leading_zeros()) as usize;
+        let lw = (usize::BITS - w.leading_zeros()) as usize;
+        let mut table = vec![monoid.e(); lh * lw * h * w];
+        let at = |a: usize, b: usize, i: usize, j: usize| ((a * lw + b) * h + i) * w + j;
+        for (i, row) in grid.into_iter().enumerate() {
+            for (j, v) in row.into_iter().enumerate() {
+                table[at(0, 0, i, j)] = v;
+            }
+        }
+        for a in 0..lh {
+            for b in 0..lw {
+                if a == 0 && b == 0 {
+                    continue;
+                }
+                for i in 0..=h - (1 << a) {
+                    for j in 0..=w - (1 << b) {
+                        table[at(a, b, i, j)] = if b > 0 {
+                            let span = 1 << (b - 1);
+                            monoid.op(
+                                &table[at(a, b - 1, i, j)],
+                                &table[at(a, b - 1, i, j + span)],
+                            )
+                        } else {
+                            let span = 1 << (a - 1);
+                            monoid.op(
+                                &table[at(a - 1, b, i, j)],
+                                &table[at(a - 1, b, i + span, j)],
+                            )
+                        };
+                    }
+                }
+            }
+        }
+        Self(table.into(), h, w, monoid)
+    }
+
+    /// 行数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.1
+    }
+
+    /// 列数を返す
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.2
+    }
+
+    /// `rows`行目と`cols`列目の範囲の長方形領域の総積を計算する
+    ///
+    /// # Constraints
+    ///
+    /// - `rows`は`0..self.height()`に含まれる区間である.
+    /// - `cols`は`0..self.width()`に含まれる区間である.
+    ///
+    /// # Time complexity
+    ///
+    /// - *O*(1)
+    #[must_use]
+    pub fn query(
+        &self,
+        rows: impl std::ops::RangeBounds<usize>,
+        cols: impl std::ops::RangeBounds<usize>,
+    ) -> M::T {
+        let (h, w) = (self.1, self.2);
+        let (top, bottom) = to_range(rows, h);
+        let (left, right) = to_range(cols, w);
+        if top == bottom || left == right {
+            return self.3.e();
+        }
+        let lw = (usize::BITS - w.leading_zeros()) as usize;
+        let a = (usize::BITS - (bottom - top).leading_zeros() - 1) as usize;
+        let b = (usize::BITS - (right - left).leading_zeros() - 1) as usize;
+        let at = |i: usize, j: usize| &self.0[((a * lw + b) * h + i) * w + j];
+        let (i, j) = (bottom - (1 << a), right - (1 << b));
+        self.3.op(
+            &self.3.op(at(top, left), at(top, j)),
+            &self.3.op(at(i, left), at(i, j)),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,4 +247,29 @@ mod tests {
         assert_eq!(table.query(3..), (3, 9));
         assert_eq!(table.query(..), (0, 9));
     }
+
+    #[test]
+    fn grid_min() {
+        use crate::monoids::Min;
+
+        let grid = (0..7)
+            .map(|i| (0..11).map(|j| (i * 5 + j * 7) % 13).collect::<Vec<i32>>())
+            .collect::<Vec<_>>();
+        let table = SparseTable2D::new(Min::new(), grid.clone());
+        for t in 0..=7 {
+            for b in t..=7 {
+                for l in 0..=11 {
+                    for r in l..=11 {
+                        let expected = grid[t..b]
+                            .iter()
+                            .flat_map(|row| &row[l..r])
+                            .copied()
+                            .min()
+                            .unwrap_or(i32::MAX);
+                        assert_eq!(table.query(t..b, l..r), expected);
+                    }
+                }
+            }
+        }
+    }
 }
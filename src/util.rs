@@ -42,7 +42,7 @@ impl_zero! { u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize }
 /// マグマ. 二項演算で閉じている代数構造
 pub trait Magma {
     /// マグマの元の型
-    type T: Eq;
+    type T;
     /// 二項演算
     fn op(&self, lhs: &Self::T, rhs: &Self::T) -> Self::T;
 }
@@ -74,3 +74,136 @@ pub trait Associativity: Magma {}
 pub trait Commutativity: Magma {}
 /// マグマが冪等則が成り立つことを表すトレイト
 pub trait Idempotence: Magma {}
+
+/// 長さ`len`の列に対する`range`を半開区間`(left, right)`に変換する
+pub(super) fn to_range(range: impl std::ops::RangeBounds<usize>, len: usize) -> (usize, usize) {
+    let left = match range.start_bound() {
+        std::ops::Bound::Included(&i) => i,
+        std::ops::Bound::Excluded(&i) => i + 1,
+        std::ops::Bound::Unbounded => 0,
+    };
+    let right = match range.end_bound() {
+        std::ops::Bound::Included(&i) => i + 1,
+        std::ops::Bound::Excluded(&i) => i,
+        std::ops::Bound::Unbounded => len,
+    };
+    debug_assert!(left <= right && right <= len);
+    (left, right)
+}
+
+/// 単位元と二項演算の関数から作るモノイド
+///
+/// `op`は結合律を満たし, `e`はその単位元である必要がある
+#[derive(Clone, Copy)]
+pub struct FnMonoid<T, Op> {
+    e: T,
+    op: Op,
+}
+
+impl<T: Clone, Op: Fn(&T, &T) -> T> FnMonoid<T, Op> {
+    /// 単位元`e`と二項演算`op`からモノイドを作る
+    #[must_use]
+    pub fn new(e: T, op: Op) -> Self {
+        Self { e, op }
+    }
+}
+
+impl<T, Op: Fn(&T, &T) -> T> Magma for FnMonoid<T, Op> {
+    type T = T;
+    fn op(&self, lhs: &T, rhs: &T) -> T {
+        (self.op)(lhs, rhs)
+    }
+}
+impl<T: Clone, Op: Fn(&T, &T) -> T> Identity for FnMonoid<T, Op> {
+    fn e(&self) -> T {
+        self.e.clone()
+    }
+}
+impl<T, Op: Fn(&T, &T) -> T> Associativity for FnMonoid<T, Op> {}
+
+/// 単位元・二項演算・逆元の関数から作る可換群
+///
+/// `op`は結合律と交換則を満たし, `e`はその単位元, `inv`は逆元を返す関数である必要がある
+#[derive(Clone, Copy)]
+pub struct FnGroup<T, Op, Inv> {
+    e: T,
+    op: Op,
+    inv: Inv,
+}
+
+impl<T: Clone, Op: Fn(&T, &T) -> T, Inv: Fn(&T) -> T> FnGroup<T, Op, Inv> {
+    /// 単位元`e`と二項演算`op`と逆元を求める関数`inv`から可換群を作る
+    #[must_use]
+    pub fn new(e: T, op: Op, inv: Inv) -> Self {
+        Self { e, op, inv }
+    }
+}
+
+impl<T, Op: Fn(&T, &T) -> T, Inv> Magma for FnGroup<T, Op, Inv> {
+    type T = T;
+    fn op(&self, lhs: &T, rhs: &T) -> T {
+        (self.op)(lhs, rhs)
+    }
+}
+impl<T: Clone, Op: Fn(&T, &T) -> T, Inv> Identity for FnGroup<T, Op, Inv> {
+    fn e(&self) -> T {
+        self.e.clone()
+    }
+}
+impl<T, Op: Fn(&T, &T) -> T, Inv: Fn(&T) -> T> Inverse for FnGroup<T, Op, Inv> {
+    fn inv(&self, v: &T) -> T {
+        (self.inv)(v)
+    }
+}
+impl<T, Op: Fn(&T, &T) -> T, Inv> Associativity for FnGroup<T, Op, Inv> {}
+impl<T, Op: Fn(&T, &T) -> T, Inv> Commutativity for FnGroup<T, Op, Inv> {}
+
+/// モノイドを表す型を定義するマクロ
+///
+/// `;`の後に`Commutativity`や`Idempotence`などの性質を列挙すると, それらのトレイトも実装される.
+/// `inv`を指定すると`Inverse`も実装される.
+///
+/// ```
+/// use ribitol::{monoid, segmenttree::SegmentTree, sparsetable::SparseTable};
+///
+/// monoid!(Min: i32 = i32::MAX, |a, b| *a.min(b); Commutativity, Idempotence);
+/// monoid!(Sum: i64 = 0, |a, b| a + b, inv |a| -a; Commutativity);
+///
+/// let seg = SegmentTree::from_vec(Min, vec![3, 1, 4, 1, 5]);
+/// assert_eq!(seg.prod(2..), 1);
+/// let table = SparseTable::new(Min, [3, 1, 4, 1, 5]);
+/// assert_eq!(table.query(..1), 3);
+/// let seg = SegmentTree::from(vec![3i64, 1, 4, 1, 5]);
+/// assert_eq!(seg.prod(1..4), 6);
+/// # let _: &SegmentTree<Sum> = &seg;
+/// ```
+#[macro_export]
+macro_rules! monoid {
+    (
+        $vis:vis $name:ident: $t:ty = $e:expr, |$a:ident, $b:ident| $op:expr
+        $(, inv |$x:ident| $inv:expr)? $(; $($prop:ident),* $(,)?)?
+    ) => {
+        #[derive(Clone, Copy, Default, Debug)]
+        $vis struct $name;
+        impl $crate::util::Magma for $name {
+            type T = $t;
+            fn op(&self, $a: &$t, $b: &$t) -> $t {
+                $op
+            }
+        }
+        impl $crate::util::Identity for $name {
+            fn e(&self) -> $t {
+                $e
+            }
+        }
+        impl $crate::util::Associativity for $name {}
+        $(
+            impl $crate::util::Inverse for $name {
+                fn inv(&self, $x: &$t) -> $t {
+                    $inv
+                }
+            }
+        )?
+        $($(impl $crate::util::$prop for $name {})*)?
+    };
+}
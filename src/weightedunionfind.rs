@@ -34,7 +34,7 @@ impl<G: Group> WeightedUnionFind<G> {
     /// 仮想的には長さ`n`の群`group`の要素からなる配列`x`が作られる.
     #[must_use]
     pub fn new(group: G, n: usize) -> Self {
-        const ONE: NonZero<usize> = unsafe { NonZero::new_unchecked(1) };
+        const ONE: NonZero<usize> = NonZero::new(1).unwrap();
 
         Self(
             std::iter::repeat_with(|| Node::Root(ONE, true))
@@ -244,7 +244,10 @@ impl<G: Group> WeightedUnionFind<G> {
     /// # Time complexity
     ///
     /// - *O*(α(*n*))
-    pub fn union(&mut self, a: usize, b: usize, diff: &G::T) {
+    pub fn union(&mut self, a: usize, b: usize, diff: &G::T)
+    where
+        G::T: PartialEq,
+    {
         debug_assert!(a < self.len());
         debug_assert!(b < self.len());
         let (a, a_size, ah) = self.diff_internal(a);